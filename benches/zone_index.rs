@@ -0,0 +1,82 @@
+//! Compares the zone index lookups with scanning all the zones.
+//! Run with `cargo bench --bench zone_index`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+#[allow(dead_code, unused_imports)]
+#[path = "../src/index.rs"]
+mod index;
+
+use index::ZoneIndex;
+
+/// `(id, low, high)` of zones spread over the price range like a few
+/// months of zones of a single symbol.
+fn zones(count: usize) -> Vec<(usize, f64, f64)> {
+    (0..count)
+        .map(|i| {
+            let low = 1000.0 + (i * 7919 % count) as f64 * 5.0;
+            (i, low, low + 1.0 + (i % 5) as f64)
+        })
+        .collect()
+}
+
+fn containing_linear(zones: &[(usize, f64, f64)], price: f64) -> Vec<usize> {
+    zones
+        .iter()
+        .filter(|(_, low, high)| *low <= price && price <= *high)
+        .map(|(id, ..)| *id)
+        .collect()
+}
+
+fn nearest_above_linear(
+    zones: &[(usize, f64, f64)],
+    price: f64,
+) -> Option<usize> {
+    zones
+        .iter()
+        .filter(|(_, low, _)| *low > price)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(id, ..)| *id)
+}
+
+fn lookups(c: &mut Criterion) {
+    for count in [100, 1000, 10000] {
+        let zones = zones(count);
+        let index = ZoneIndex::from_zones(zones.iter().copied());
+        let price = 1000.0 + count as f64 * 2.5 + 0.5;
+
+        c.bench_function(&format!("containing/index/{count}"), |b| {
+            b.iter(|| index.containing(black_box(price)))
+        });
+        c.bench_function(&format!("containing/linear/{count}"), |b| {
+            b.iter(|| containing_linear(&zones, black_box(price)))
+        });
+        c.bench_function(&format!("nearest/index/{count}"), |b| {
+            b.iter(|| {
+                (
+                    index.nearest_above(black_box(price)),
+                    index.nearest_below(black_box(price)),
+                )
+            })
+        });
+        c.bench_function(&format!("nearest/linear/{count}"), |b| {
+            b.iter(|| nearest_above_linear(&zones, black_box(price)))
+        });
+    }
+}
+
+fn edits(c: &mut Criterion) {
+    let zones = zones(1000);
+    let mut index = ZoneIndex::from_zones(zones.iter().copied());
+    let mut step = 0.0;
+
+    c.bench_function("edit/index/1000", |b| {
+        b.iter(|| {
+            step = (step + 7.0) % 5000.0;
+            index.insert(black_box(500), 1000.0 + step, 1003.0 + step);
+        })
+    });
+}
+
+criterion_group!(benches, lookups, edits);
+criterion_main!(benches);
@@ -1,37 +1,378 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use tui::{
     style::{Modifier, Style},
     text::{Span, Spans},
 };
 
-use crate::bot::PriceLevel;
+use crate::{
+    bot::PriceLevel, config::Theme, price::Price, strategy::ZonePriority,
+    venue::VenueKind,
+};
 
 /// Alert which holds information about the time it ocurred,
 /// suggested position and other important info.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Alert {
     time_created: Instant,
     price: PriceLevel,
     position: Position,
     cause: String,
+    /// Zone which caused the alert.
+    zone: Option<AlertZone>,
+    /// Prices on the two venues which moved apart and caused the alert.
+    divergence: Option<AlertDivergence>,
+    /// Where the alert is delivered.
+    sinks: Vec<AlertSink>,
+
+    /// Biggest move in the suggested direction since the alert, in percent.
+    best_move: f64,
+    /// Biggest move against the suggested direction since the alert.
+    worst_move: f64,
+    outcome: Option<Outcome>,
 }
 
 impl Alert {
-    fn elapsed_time(&self) -> u64 {
-        self.time_created.elapsed().as_secs()
+    /// Price move in percent needed to decide whether the alert was a hit.
+    pub const HIT_MOVE: f64 = 1.0;
+    /// Alerts undecided after this long are considered expired.
+    pub const EVALUATION_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+    pub fn new(
+        time_created: Instant,
+        price: PriceLevel,
+        position: Position,
+        cause: String,
+    ) -> Self {
+        Self {
+            time_created,
+            price,
+            position,
+            cause,
+            zone: None,
+            divergence: None,
+            sinks: AlertRules::DEFAULT_SINKS.to_vec(),
+            best_move: 0.0,
+            worst_move: 0.0,
+            outcome: None,
+        }
+    }
+
+    pub fn with_zone(mut self, zone: AlertZone) -> Self {
+        self.zone = Some(zone);
+        self
+    }
+
+    pub fn with_divergence(mut self, divergence: AlertDivergence) -> Self {
+        self.divergence = Some(divergence);
+        self
+    }
+
+    /// Restores how the price moved after the alert, e.g. when
+    /// the alert is read back from the alert log.
+    pub fn with_outcome(
+        mut self,
+        outcome: Option<Outcome>,
+        best_move: f64,
+        worst_move: f64,
+    ) -> Self {
+        self.outcome = outcome;
+        self.best_move = best_move;
+        self.worst_move = worst_move;
+        self
+    }
+
+    pub fn with_sinks(mut self, sinks: &[AlertSink]) -> Self {
+        self.sinks = sinks.to_vec();
+        self
+    }
+
+    /// Combines the alerts raised at once, e.g. by nested zones, into one.
+    /// The alert of the highest priority zone decides the position and the
+    /// causes of the rest are listed after its cause, the alerts without
+    /// a zone rank last. The combined alert is delivered to the sinks of all
+    /// the alerts.
+    pub fn combine(alerts: Vec<Alert>) -> Option<Alert> {
+        let primary = alerts
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, a)| (a.zone.is_none(), a.zone.map(|z| z.priority)))
+            .map(|(i, _)| i)?;
+        let mut alerts = alerts;
+        let mut combined = alerts.remove(primary);
+        for alert in alerts {
+            combined.cause.push_str("; also ");
+            combined.cause.push_str(&alert.cause);
+            for sink in alert.sinks {
+                if !combined.sinks.contains(&sink) {
+                    combined.sinks.push(sink);
+                }
+            }
+        }
+        Some(combined)
+    }
+
+    /// Whether the alert should be delivered to the `sink`.
+    pub fn notifies(&self, sink: AlertSink) -> bool {
+        self.sinks.contains(&sink)
+    }
+
+    /// Follows the price after the alert to find out whether the price
+    /// moved [`Alert::HIT_MOVE`] percent in the suggested direction before
+    /// it moved as much against it.
+    pub fn track(&mut self, price: PriceLevel, now: Instant) {
+        if self.outcome.is_some() || price.0.is_nan() {
+            return;
+        }
+        let change = self.price.distance_percent(price);
+        let moved = match self.position {
+            Position::Buy => change,
+            Position::Sell => -change,
+        };
+        self.best_move = self.best_move.max(moved);
+        self.worst_move = self.worst_move.min(moved);
+
+        if moved >= Self::HIT_MOVE {
+            self.outcome = Some(Outcome::Hit);
+        } else if moved <= -Self::HIT_MOVE {
+            self.outcome = Some(Outcome::Miss);
+        } else if now.saturating_duration_since(self.time_created)
+            >= Self::EVALUATION_WINDOW
+        {
+            self.outcome = Some(Outcome::Expired);
+        }
+    }
+
+    pub fn time_created(&self) -> Instant {
+        self.time_created
+    }
+
+    pub fn price(&self) -> PriceLevel {
+        self.price
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    pub fn cause(&self) -> &str {
+        &self.cause
+    }
+
+    pub fn zone(&self) -> Option<AlertZone> {
+        self.zone
+    }
+
+    pub fn best_move(&self) -> f64 {
+        self.best_move
+    }
+
+    pub fn worst_move(&self) -> f64 {
+        self.worst_move
+    }
+
+    pub fn outcome(&self) -> Option<Outcome> {
+        self.outcome
+    }
+
+    /// Seconds passed between the alert creation and `now`.
+    pub fn elapsed_time(&self, now: Instant) -> u64 {
+        now.saturating_duration_since(self.time_created).as_secs()
     }
 
-    fn text(&self) -> Vec<Spans> {
-        vec![Spans::from(vec![Span::styled(
-            "content",
-            Style::default().add_modifier(Modifier::SLOW_BLINK),
-        )])]
+    pub fn text(&self, now: Instant, theme: &Theme) -> Vec<Spans<'static>> {
+        let position_color = match self.position {
+            Position::Buy => theme.up,
+            Position::Sell => theme.down,
+        };
+        let mut text = vec![
+            Spans::from(vec![
+                Span::styled(
+                    format!("\u{2757}Alert at {}\u{2757}", self.price.0),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(format!(
+                    " Time: {} ago",
+                    format_age(Duration::from_secs(self.elapsed_time(now)))
+                )),
+            ]),
+            Spans::from(vec![
+                Span::raw("Position: (Possible) "),
+                Span::styled(
+                    format!("{:?}", self.position),
+                    Style::default().fg(position_color),
+                ),
+            ]),
+            Spans::from(Span::raw(self.cause.clone())),
+        ];
+        if let Some(divergence) = self.divergence {
+            text.push(Spans::from(Span::raw(format!(
+                "{} {} vs {} {}",
+                divergence.venue,
+                self.price.0,
+                divergence.other_venue,
+                divergence.other_price.0
+            ))));
+        }
+        text
     }
 }
 
-#[derive(Debug)]
-enum Position {
+/// Formats the age with the two most significant units, e.g. `5m 3s`.
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub enum Position {
     Buy,
     Sell,
 }
+
+/// Zone's override of the global alert behavior.
+/// Fields which are not set fall back to the defaults.
+#[derive(
+    Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+#[serde(default)]
+pub struct AlertRules {
+    /// Price movements which raise an alert.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub triggers: Option<Vec<AlertTrigger>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sinks: Option<Vec<AlertSink>>,
+    /// Alert message with the `{event}`, `{price}`, `{priority}`, `{low}`,
+    /// `{high}`, `{note}` and `{tags}` placeholders.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl AlertRules {
+    pub const DEFAULT_TRIGGERS: &'static [AlertTrigger] = &[
+        AlertTrigger::FromAbove,
+        AlertTrigger::FromBelow,
+        AlertTrigger::Approach,
+    ];
+    pub const DEFAULT_SINKS: &'static [AlertSink] =
+        &[AlertSink::Panel, AlertSink::Log];
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn triggers(&self) -> &[AlertTrigger] {
+        self.triggers.as_deref().unwrap_or(Self::DEFAULT_TRIGGERS)
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub enum AlertTrigger {
+    /// Price entered the zone from above.
+    FromAbove,
+    /// Price entered the zone from below.
+    FromBelow,
+    /// Price closed through the zone.
+    Break,
+    /// Price entered the zone and left it on the side it came from.
+    Reject,
+    /// Price came close to the zone.
+    Approach,
+}
+
+impl AlertTrigger {
+    /// Describes the trigger in the alert message.
+    pub fn event(self) -> &'static str {
+        match self {
+            AlertTrigger::FromAbove | AlertTrigger::FromBelow => "entered",
+            AlertTrigger::Break => "broke through",
+            AlertTrigger::Reject => "was rejected from",
+            AlertTrigger::Approach => "approached",
+        }
+    }
+}
+
+/// Places the alert can be delivered to.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub enum AlertSink {
+    /// Alert list in the UI.
+    Panel,
+    /// Alert log file.
+    Log,
+    /// Terminal bell.
+    Bell,
+}
+
+/// Bounds of the zone which caused the alert.
+#[derive(Debug, Clone, Copy)]
+pub struct AlertZone {
+    pub priority: ZonePriority,
+    pub low: Price,
+    pub high: Price,
+}
+
+/// Price of the pair on another venue which moved away from the price
+/// of the alert.
+#[derive(Debug, Clone, Copy)]
+pub struct AlertDivergence {
+    /// Venue the price of the alert is from.
+    pub venue: VenueKind,
+    pub other_venue: VenueKind,
+    pub other_price: PriceLevel,
+}
+
+/// Whether the price followed the suggested position after the alert.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub enum Outcome {
+    Hit,
+    Miss,
+    /// Price did not move enough in either direction.
+    Expired,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::{Alert, AlertZone, Position};
+    use crate::{bot::PriceLevel, price::Price, strategy::ZonePriority};
+
+    #[test]
+    fn combined_alert_follows_the_highest_priority_zone() {
+        let now = Instant::now();
+        let zone = |priority| AlertZone {
+            priority,
+            low: Price::new(1000, 0),
+            high: Price::new(1100, 0),
+        };
+        let alerts = vec![
+            Alert::new(
+                now,
+                PriceLevel(990.0),
+                Position::Sell,
+                "diverged".into(),
+            ),
+            Alert::new(now, PriceLevel(1050.0), Position::Sell, "low".into())
+                .with_zone(zone(ZonePriority::Low)),
+            Alert::new(now, PriceLevel(1050.0), Position::Buy, "high".into())
+                .with_zone(zone(ZonePriority::High)),
+        ];
+
+        let combined = Alert::combine(alerts).unwrap();
+        assert_eq!(combined.position(), Position::Buy);
+        assert_eq!(combined.price().0, 1050.0);
+        assert_eq!(combined.cause(), "high; also diverged; also low");
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    sync::{
+        mpsc::{channel, Receiver},
+        Arc,
+    },
+    thread,
+};
+
+use crate::{
+    price::Price,
+    strategy::{Zone, ZonePriority},
+    symbol::Symbol,
+    venue::Venue,
+};
+
+/// Candle interval in minutes used for the zone detection.
+const INTERVAL: u32 = 60;
+/// Number of candles used for the zone detection.
+const CANDLE_LIMIT: u16 = 500;
+/// Candles on each side which a swing high or low has to exceed.
+const SWING_WINDOW: usize = 3;
+/// Number of price bins in the volume profile.
+const VOLUME_BINS: usize = 50;
+/// Volume peak has to be this many times bigger than the average bin.
+const VOLUME_PEAK_RATIO: f64 = 1.5;
+/// Price levels closer than this percent are clustered together.
+const CLUSTER_TOLERANCE: f64 = 0.5;
+/// Minimum zone height in percent of its price.
+const MIN_ZONE_HEIGHT: f64 = 0.1;
+/// Levels touched fewer times are not proposed as zones.
+const MIN_TOUCHES: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candle {
+    pub high: f64,
+    pub low: f64,
+    pub volume: f64,
+}
+
+/// Zone proposed by the detection which the user can accept or reject.
+#[derive(Debug, Clone)]
+pub struct SuggestedZone {
+    pub zone: Zone,
+    /// How many swing points and volume peaks formed the zone.
+    pub touches: usize,
+}
+
+/// Fetching the candles takes a while so the zones are detected
+/// in a separate thread, the same way the price is read.
+pub fn spawn_zone_detection(
+    venue: Arc<dyn Venue>,
+    symbol: Symbol,
+) -> Receiver<Result<Vec<SuggestedZone>, String>> {
+    let (tx, rx) = channel();
+
+    thread::spawn(move || {
+        let result = venue
+            .candles(&symbol, INTERVAL, CANDLE_LIMIT)
+            .map(|candles| detect_zones(&candles));
+        // The bot might not wait for the result anymore.
+        let _ = tx.send(result);
+    });
+    rx
+}
+
+/// Proposes support and resistance zones by clustering the swing highs,
+/// swing lows and volume profile peaks of the `candles`. The more levels
+/// form a zone the higher its priority.
+pub fn detect_zones(candles: &[Candle]) -> Vec<SuggestedZone> {
+    let mut levels = swing_points(candles);
+    levels.extend(volume_peaks(candles));
+    levels.retain(|level| level.is_finite() && *level > 0.0);
+    levels.sort_by(f64::total_cmp);
+
+    cluster(&levels)
+        .into_iter()
+        .filter(|cluster| cluster.len() >= MIN_TOUCHES)
+        .filter_map(|cluster| {
+            let touches = cluster.len();
+            let (mut low, mut high) = (cluster[0], cluster[touches - 1]);
+            let min_height = low * MIN_ZONE_HEIGHT / 100.0;
+            if high - low < min_height {
+                let middle = (low + high) / 2.0;
+                low = middle - min_height / 2.0;
+                high = middle + min_height / 2.0;
+            }
+            let priority = match touches {
+                0..=2 => ZonePriority::Low,
+                3 => ZonePriority::Medium,
+                _ => ZonePriority::High,
+            };
+            let (low, high) = (Price::from_f64(low)?, Price::from_f64(high)?);
+            let mut zone = Zone::new(priority, low, high);
+            zone.note = Some(format!("Detected, {touches} touches"));
+            Some(SuggestedZone { zone, touches })
+        })
+        .collect()
+}
+
+/// Highs and lows which are the most extreme
+/// within [`SWING_WINDOW`] candles on each side.
+fn swing_points(candles: &[Candle]) -> Vec<f64> {
+    let mut points = Vec::new();
+    if candles.len() < 2 * SWING_WINDOW + 1 {
+        return points;
+    }
+    for i in SWING_WINDOW..candles.len() - SWING_WINDOW {
+        let window = &candles[i - SWING_WINDOW..=i + SWING_WINDOW];
+        let candle = candles[i];
+        if window.iter().all(|c| c.high <= candle.high) {
+            points.push(candle.high);
+        }
+        if window.iter().all(|c| c.low >= candle.low) {
+            points.push(candle.low);
+        }
+    }
+    points
+}
+
+/// Price levels with locally the most traded volume. Volume of every candle
+/// is spread evenly over the bins between its low and high.
+fn volume_peaks(candles: &[Candle]) -> Vec<f64> {
+    let min = candles.iter().map(|c| c.low).fold(f64::INFINITY, f64::min);
+    let max = candles
+        .iter()
+        .map(|c| c.high)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let bin_size = (max - min) / VOLUME_BINS as f64;
+    if bin_size.is_nan() || bin_size <= 0.0 {
+        return Vec::new();
+    }
+
+    let bin_of =
+        |price: f64| (((price - min) / bin_size) as usize).min(VOLUME_BINS - 1);
+    let mut bins = [0.0; VOLUME_BINS];
+    for candle in candles {
+        let (first, last) = (bin_of(candle.low), bin_of(candle.high));
+        let share = candle.volume / (last - first + 1) as f64;
+        for bin in &mut bins[first..=last] {
+            *bin += share;
+        }
+    }
+
+    let threshold =
+        bins.iter().sum::<f64>() / VOLUME_BINS as f64 * VOLUME_PEAK_RATIO;
+    (0..VOLUME_BINS)
+        .filter(|&i| {
+            let left = if i > 0 { bins[i - 1] } else { 0.0 };
+            let right = bins.get(i + 1).copied().unwrap_or(0.0);
+            bins[i] > threshold && bins[i] >= left && bins[i] >= right
+        })
+        .map(|i| min + (i as f64 + 0.5) * bin_size)
+        .collect()
+}
+
+/// Groups the sorted `levels` so that every level in a group is within
+/// [`CLUSTER_TOLERANCE`] percent of the lowest level in that group.
+fn cluster(levels: &[f64]) -> Vec<Vec<f64>> {
+    let mut clusters: Vec<Vec<f64>> = Vec::new();
+    for &level in levels {
+        match clusters.last_mut() {
+            Some(cluster)
+                if (level - cluster[0]) / cluster[0] * 100.0
+                    <= CLUSTER_TOLERANCE =>
+            {
+                cluster.push(level)
+            }
+            _ => clusters.push(vec![level]),
+        }
+    }
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_zones, swing_points, Candle};
+    use crate::strategy::ZonePriority;
+
+    fn candle(low: f64, high: f64) -> Candle {
+        Candle {
+            high,
+            low,
+            volume: 10.0,
+        }
+    }
+
+    /// Price bouncing between the support around 100 and
+    /// the resistance around 120 several times.
+    fn ranging_market() -> Vec<Candle> {
+        let mut candles = Vec::new();
+        for bounce in 0..4 {
+            let wiggle = bounce as f64 * 0.1;
+            for step in 0..5 {
+                let low = 100.0 + wiggle + step as f64 * 4.0;
+                candles.push(candle(low, low + 3.0));
+            }
+            for step in (0..5).rev() {
+                let low = 101.0 + wiggle + step as f64 * 4.0;
+                candles.push(candle(low, low + 2.5));
+            }
+        }
+        candles
+    }
+
+    #[test]
+    fn swing_points_are_local_extremes() {
+        let candles: Vec<Candle> = [5.0, 4.0, 3.0, 2.0, 3.0, 4.0, 5.0]
+            .iter()
+            .map(|&low| candle(low, low + 1.0))
+            .collect();
+
+        assert_eq!(swing_points(&candles), [2.0]);
+    }
+
+    #[test]
+    fn repeated_bounces_become_zones() {
+        let zones = detect_zones(&ranging_market());
+
+        let support = zones
+            .iter()
+            .find(|s| s.zone.contains(crate::bot::PriceLevel(100.2)))
+            .expect("support was not detected");
+        assert!(support.touches >= 3);
+        assert!(!matches!(support.zone.priority, ZonePriority::Low));
+        assert!(zones
+            .iter()
+            .any(|s| s.zone.low_f64() > 117.0 && s.zone.high_f64() < 121.0));
+    }
+}
@@ -1,136 +1,788 @@
 use std::{
+    collections::VecDeque,
     sync::{
-        mpsc::{channel, Receiver},
+        atomic::{AtomicU64, Ordering},
+        mpsc::{Receiver, TryRecvError},
         Arc,
     },
-    thread,
-};
-
-use binance::{
-    api::Binance, errors::Result as BinanceResult, market::Market,
-    model::SymbolPrice,
+    time::Duration,
 };
 
 use crate::{
-    alert::Alert,
-    strategy::{Zone, ZoneStrat},
+    alert::{Alert, AlertSink, Position},
+    analysis::{self, SuggestedZone},
+    clock::Clock,
+    depth::{DepthWatch, Liquidity, OrderBook},
+    divergence::DivergenceWatch,
+    execution::{Executor, OrderRecord},
+    feed::{MarketEvent, MarketFeed, PriceStream},
+    paper::{PaperConfig, PaperTrader},
+    price::Price,
+    risk::{RiskConfig, RiskPlan},
+    save::{AlertLog, AlertRecord},
+    session::SessionRecorder,
+    strategy::{AlertDefaults, Proximity, Strategy, Zone, ZoneRole, ZoneStrat},
+    symbol::{Symbol, SymbolInfo},
+    venue::{Venue, VenueKind},
 };
 
 pub struct MarketBot {
-    market: Arc<Market>,
+    /// Where the candles for the zone detection are read from.
+    venue: Arc<dyn Venue>,
+    /// Reads the prices from the market, `None` if they are replayed.
+    feed: Option<MarketFeed>,
+    /// Prices and order books sent by the feed or the replay.
+    events: Receiver<MarketEvent>,
+    /// Milliseconds between two price reads, shared with the feed
+    /// so the interval can change while it runs.
+    tick_interval: Arc<AtomicU64>,
     symbol: Symbol,
+    symbol_info: Option<SymbolInfo>,
     price_tracker: PriceTracker,
+    /// Price on another venue compared with the price of the bot,
+    /// `None` if the prices are not compared.
+    comparison: Option<Comparison>,
+    /// Order book of the symbol, `None` if it is not read.
+    depth: Option<DepthTracker>,
+    clock: Arc<dyn Clock>,
+    recorder: Option<SessionRecorder>,
 
     zone: ZoneStrat,
-    latest_alert: Option<Alert>,
+    /// Smallest price step of the symbol, `None` if it is not known.
+    tick_size: Option<Price>,
+    /// All alerts since the start, oldest first.
+    alerts: Vec<Alert>,
+    alert_log: Option<AlertLog>,
+    /// Indices of the alerts which are not written to the log yet.
+    unlogged_alerts: Vec<usize>,
+    /// Simulated positions opened from the alerts.
+    paper: PaperTrader,
+    /// Account settings of the risk calculator.
+    risk: RiskConfig,
+    /// Places the orders of the alerts, `None` if the execution is off.
+    executor: Option<Executor>,
+    /// Whether an alert asked for the terminal bell.
+    ring_bell: bool,
+
+    /// Zones proposed by the analysis waiting for the user's approval.
+    suggestions: Vec<SuggestedZone>,
+    detection: DetectionState,
 
     tick: u16,
+    /// The price is analyzed once per this many ticks.
+    analysis_ticks: u16,
 }
 
-// TODO replace unwrap() with ?
 impl MarketBot {
-    const UPDATE_TICKS: u16 = 5;
+    pub const DEFAULT_TICK_INTERVAL: Duration = Duration::from_millis(2000);
+    pub const DEFAULT_ANALYSIS_TICKS: u16 = 5;
+
+    /// Creates the bot which reads the prices and the candles of the market
+    /// from the `venue`.
+    pub fn new<S: Into<Symbol>>(
+        symbol: S,
+        zones: Vec<Zone>,
+        venue: Arc<dyn Venue>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        let symbol = symbol.into();
+        let tick_interval = Arc::new(AtomicU64::new(
+            Self::DEFAULT_TICK_INTERVAL.as_millis() as u64,
+        ));
+        let (feed, events) =
+            MarketFeed::start(clock.clone(), tick_interval.clone());
+        feed.poll(venue.kind(), venue.clone());
+        feed.subscribe(venue.kind(), vec![symbol.clone()]);
+        let mut bot = Self {
+            feed: Some(feed),
+            tick_interval,
+            ..Self::with_price_reader(symbol, zones, events, venue, clock)
+        };
+        bot.watch_depth();
+        bot
+    }
 
-    pub fn new<S: Into<Symbol>>(symbol: S, zones: Vec<Zone>) -> Self {
+    /// Creates the bot which receives the prices of its venue and the order
+    /// books from the provided `reader` instead of reading them from the
+    /// market. The candles are still read from the `venue`.
+    pub fn with_price_reader<S: Into<Symbol>>(
+        symbol: S,
+        zones: Vec<Zone>,
+        reader: Receiver<MarketEvent>,
+        venue: Arc<dyn Venue>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
         let symbol = symbol.into();
-        let market = Arc::new(Market::new(None, None));
-        let price_tracker = PriceTracker::new(market.clone(), symbol);
         Self {
-            market: market,
+            venue,
+            feed: None,
+            events: reader,
+            tick_interval: Arc::new(AtomicU64::new(
+                Self::DEFAULT_TICK_INTERVAL.as_millis() as u64,
+            )),
+            price_tracker: PriceTracker::new(),
+            comparison: None,
+            depth: None,
             symbol,
-            price_tracker,
+            symbol_info: None,
+            clock,
+            recorder: None,
 
             zone: ZoneStrat::from_zones(zones),
-            latest_alert: None,
+            tick_size: None,
+            alerts: Vec::new(),
+            alert_log: None,
+            unlogged_alerts: Vec::new(),
+            paper: PaperTrader::new(PaperConfig::default()),
+            risk: RiskConfig::default(),
+            executor: None,
+            ring_bell: false,
+
+            suggestions: Vec::new(),
+            detection: DetectionState::Idle,
 
             tick: 0,
+            analysis_ticks: Self::DEFAULT_ANALYSIS_TICKS,
+        }
+    }
+
+    pub fn analyze(&mut self) {
+        let price = self.get_price();
+        let now = self.clock.now();
+        let mut alerts =
+            self.zone
+                .update_lifecycle(price, self.clock.unix_time(), now);
+        alerts.extend(self.zone.analyze(price, now));
+        if let Some(alert) = Alert::combine(alerts) {
+            self.raise_alert(alert, true);
         }
     }
 
-    pub fn analyze(&mut self) {}
+    /// Delivers the `alert` to its sinks. The `tradable` alerts of the zone
+    /// strategy also open their paper position and propose their order,
+    /// the other signals are only shown.
+    fn raise_alert(&mut self, alert: Alert, tradable: bool) {
+        self.ring_bell |= alert.notifies(AlertSink::Bell);
+        if tradable {
+            if let Some(zone) = alert.zone() {
+                let target =
+                    self.zone.next_zone(zone.low, zone.high, alert.position());
+                self.paper.open(&alert, target);
+            }
+            if let Some(executor) = &mut self.executor {
+                executor.propose(&alert);
+            }
+        }
+        self.unlogged_alerts.push(self.alerts.len());
+        self.alerts.push(alert);
+    }
+
+    /// Streams the prices of the venue from the `stream` instead of
+    /// polling them. The replays keep sending their prices.
+    pub fn stream_prices(&self, stream: Arc<dyn PriceStream>) {
+        if let Some(feed) = &self.feed {
+            feed.stream(self.venue.kind(), stream);
+        }
+    }
+
+    /// Compares the price with the price on the `venue` and alerts when
+    /// they differ by more than `threshold` percent. The prices of the
+    /// `venue` are read by the same feed as the prices of the bot.
+    pub fn compare_with(&mut self, venue: Arc<dyn Venue>, threshold: f64) {
+        let kind = venue.kind();
+        if let Some(feed) = &self.feed {
+            feed.poll(kind, venue);
+            feed.subscribe(kind, vec![self.symbol.clone()]);
+        }
+        self.compare_prices(kind, threshold);
+    }
+
+    /// Compares the price with the prices of the `venue` received
+    /// with the other events instead of reading them from the venue.
+    pub fn compare_prices(&mut self, venue: VenueKind, threshold: f64) {
+        self.comparison = Some(Comparison {
+            watch: DivergenceWatch::new(self.venue.kind(), venue, threshold),
+            venue,
+            tracker: PriceTracker::new(),
+        });
+    }
+
+    fn check_divergence(&mut self) {
+        let Some(comparison) = &mut self.comparison else {
+            return;
+        };
+        let alert = comparison.watch.check(
+            self.price_tracker.get_price(),
+            comparison.tracker.get_price(),
+            self.clock.now(),
+            self.zone.alert_defaults(),
+        );
+        if let Some(alert) = alert {
+            self.raise_alert(alert, false);
+        }
+    }
+
+    /// Alerts when a wall appears or disappears inside a high priority
+    /// zone. The order books are read from the market if the bot reads it,
+    /// otherwise they are received with the other events, e.g. the
+    /// replayed snapshots.
+    pub fn watch_depth(&mut self) {
+        if let Some(feed) = &self.feed {
+            feed.watch_depth(self.venue.clone(), self.symbol.clone());
+        }
+        self.depth = Some(DepthTracker {
+            book: None,
+            watch: DepthWatch::default(),
+        });
+    }
+
+    /// Takes the latest order book of the tick.
+    fn track_depth(&mut self, book: OrderBook) {
+        let Some(depth) = &mut self.depth else {
+            return;
+        };
+        let alerts = depth.watch.update(
+            &book,
+            self.zone.zones(),
+            self.price_tracker.get_price(),
+            self.clock.now(),
+            self.zone.alert_defaults(),
+        );
+        depth.book = Some(book);
+        // The walls may suggest opposite sides so they are not combined.
+        for alert in alerts {
+            self.raise_alert(alert, false);
+        }
+    }
+
+    /// Bid and ask quantity resting inside the `zone`, `None` before
+    /// the first order book is read.
+    pub fn zone_liquidity(&self, zone: &Zone) -> Option<Liquidity> {
+        let book = self.depth.as_ref()?.book.as_ref()?;
+        Some(book.liquidity(zone.low, zone.high))
+    }
+
+    /// Starts detecting zones from the historical prices in the background.
+    /// The detected zones show up in [`MarketBot::suggestions`].
+    pub fn detect_zones(&mut self) {
+        if !matches!(self.detection, DetectionState::Running(_)) {
+            let reader = analysis::spawn_zone_detection(
+                self.venue.clone(),
+                self.symbol.clone(),
+            );
+            self.detection = DetectionState::Running(reader);
+        }
+    }
+
+    fn check_detection(&mut self) {
+        let DetectionState::Running(reader) = &self.detection else {
+            return;
+        };
+        self.detection = match reader.try_recv() {
+            Ok(Ok(suggestions)) => {
+                // Suggest only the zones the user does not have yet.
+                let zones = self.zone.zones();
+                self.suggestions = suggestions
+                    .into_iter()
+                    .filter(|s| !zones.iter().any(|z| z.overlaps(&s.zone)))
+                    .collect();
+                DetectionState::Idle
+            }
+            Ok(Err(e)) => DetectionState::Failed(e.to_string()),
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                DetectionState::Failed(String::from("Detection stopped"))
+            }
+        };
+    }
+
+    /// Moves the suggested zone to the active zones and returns it
+    /// so it can be saved as the `saved` zone.
+    pub fn accept_suggestion(
+        &mut self,
+        index: usize,
+        saved: usize,
+    ) -> Option<SuggestedZone> {
+        if index >= self.suggestions.len() {
+            return None;
+        }
+        let mut suggestion = self.suggestions.remove(index);
+        suggestion.zone.saved = Some(saved);
+        suggestion.zone.low = self.round_to_tick(suggestion.zone.low);
+        suggestion.zone.high = self.round_to_tick(suggestion.zone.high);
+        suggestion.zone.created = Some(self.clock.unix_time());
+        self.zone.add_zone(suggestion.zone.clone());
+        self.zone.update_closest(self.get_price());
+        Some(suggestion)
+    }
+
+    /// Returns whether any alert since the last call
+    /// asked for the terminal bell.
+    pub fn take_bell(&mut self) -> bool {
+        std::mem::take(&mut self.ring_bell)
+    }
+
+    /// Returns whether the lifecycle of any zone changed since the last
+    /// call so the zones can be saved.
+    pub fn take_zones_changed(&mut self) -> bool {
+        self.zone.take_changed()
+    }
+
+    /// Moves the zone to the new bounds rounded to the tick size.
+    pub fn edit_zone(
+        &mut self,
+        index: usize,
+        low: Price,
+        high: Price,
+    ) -> Result<(), &'static str> {
+        if index >= self.zone.zones().len() {
+            return Err("there is no such zone");
+        }
+        let (low, high) = (self.round_to_tick(low), self.round_to_tick(high));
+        self.zone.edit_zone(index, low, high)?;
+        self.zone.update_closest(self.get_price());
+        Ok(())
+    }
+
+    /// Switches to the `symbol` with its `zones` and alert `history`.
+    /// The prices are read from the `reader` or from the market if it is
+    /// `None`, which only works if the bot reads the market already.
+    /// The old symbol is no longer read, its alerts are logged, its paper
+    /// positions closed and its order proposal rejected.
+    /// Fails if the executor holds a position of the old symbol.
+    pub fn switch_symbol(
+        &mut self,
+        symbol: Symbol,
+        zones: Vec<Zone>,
+        history: Vec<Alert>,
+        reader: Option<Receiver<MarketEvent>>,
+    ) -> Result<(), &'static str> {
+        if self.executor.as_ref().is_some_and(|e| e.position() != 0.0) {
+            return Err("close the open position first");
+        }
+        if reader.is_none() && self.feed.is_none() {
+            return Err("symbols can't be switched in a replay");
+        }
+
+        self.flush_alert_log();
+        let kind = self.venue.kind();
+        if let Some(feed) = &self.feed {
+            feed.unsubscribe(kind, self.symbol.clone());
+            if reader.is_none() {
+                feed.subscribe(kind, vec![symbol.clone()]);
+            }
+        }
+        if let Some(reader) = reader {
+            self.events = reader;
+        }
+        self.price_tracker.reset();
+        if let Some(comparison) = &mut self.comparison {
+            if let Some(feed) = &self.feed {
+                feed.unsubscribe(comparison.venue, self.symbol.clone());
+                feed.subscribe(comparison.venue, vec![symbol.clone()]);
+            }
+            comparison.tracker.reset();
+            comparison.watch.reset();
+        }
+        if let Some(depth) = &mut self.depth {
+            if let Some(feed) = &self.feed {
+                feed.watch_depth(self.venue.clone(), symbol.clone());
+            }
+            depth.book = None;
+            depth.watch.reset();
+        }
+        self.symbol = symbol;
+        self.symbol_info = None;
+        self.tick_size = None;
+        self.zone.replace_zones(zones);
+        self.alerts = history;
+        self.paper.close_all();
+        if let Some(executor) = &mut self.executor {
+            executor.reject();
+            executor.set_symbol_info(None);
+        }
+        self.suggestions.clear();
+        self.detection = DetectionState::Idle;
+        self.tick = 0;
+        Ok(())
+    }
+
+    /// Sets the trading rules of the symbol. The tick size of the rules is
+    /// used unless it is set explicitly afterwards.
+    pub fn set_symbol_info(&mut self, info: SymbolInfo) {
+        self.tick_size = info.tick_size;
+        if let Some(executor) = &mut self.executor {
+            executor.set_symbol_info(Some(info.clone()));
+        }
+        self.symbol_info = Some(info);
+    }
+
+    /// Sets the smallest price step the zone bounds are rounded to
+    /// and the prices are written with.
+    pub fn set_tick_size(&mut self, tick_size: Price) {
+        self.tick_size = tick_size.is_positive().then_some(tick_size);
+    }
+
+    pub fn tick_size(&self) -> Option<Price> {
+        self.tick_size
+    }
+
+    fn round_to_tick(&self, price: Price) -> Price {
+        match self.tick_size {
+            Some(tick) => price.round_to(tick),
+            None => price,
+        }
+    }
+
+    pub fn set_max_breaks(&mut self, max_breaks: u32) {
+        self.zone.set_max_breaks(max_breaks);
+    }
+
+    /// Sets how often the price is read and the bot ticks.
+    /// The feed picks the new interval up after its current wait.
+    pub fn set_tick_interval(&mut self, interval: Duration) {
+        self.tick_interval
+            .store(interval.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    pub fn tick_interval(&self) -> Duration {
+        Duration::from_millis(self.tick_interval.load(Ordering::Relaxed))
+    }
+
+    /// Sets after how many ticks the price is analyzed.
+    pub fn set_analysis_ticks(&mut self, ticks: u16) {
+        self.analysis_ticks = ticks.max(1);
+    }
+
+    pub fn set_alert_defaults(&mut self, defaults: AlertDefaults) {
+        self.zone.set_alert_defaults(defaults);
+    }
+
+    pub fn set_paper_config(&mut self, config: PaperConfig) {
+        self.paper = PaperTrader::new(config);
+    }
+
+    pub fn set_risk_config(&mut self, config: RiskConfig) {
+        self.risk = config;
+    }
+
+    /// Plans the trade at the zone at `index`. Zones below the price are
+    /// traded long and zones above it short. The price inside of the zone
+    /// trades the zone's role, long if the role is not known yet.
+    pub fn risk_plan(&self, index: usize) -> Option<RiskPlan> {
+        let zone = self.zone.zones().get(index)?;
+        let price = self.get_price();
+        let side = if price.0 > zone.high_f64() {
+            Position::Buy
+        } else if price.0 < zone.low_f64() {
+            Position::Sell
+        } else {
+            match zone.role {
+                Some(ZoneRole::Resistance) => Position::Sell,
+                _ => Position::Buy,
+            }
+        };
+        let target = self.zone.next_zone(zone.low, zone.high, side);
+        Some(RiskPlan::new(zone, side, target, price, &self.risk))
+    }
+
+    /// Turns the alerts into orders which wait for the user's confirmation.
+    /// The orders follow the trading rules of the symbol if they are known.
+    pub fn execute_orders(&mut self, mut executor: Executor) {
+        executor.set_symbol_info(self.symbol_info.clone());
+        self.executor = Some(executor);
+    }
+
+    /// Places the order waiting for the confirmation.
+    pub fn confirm_order(&mut self) -> Result<&OrderRecord, String> {
+        let executor =
+            self.executor.as_mut().ok_or("the order execution is off")?;
+        executor.confirm(self.symbol.as_str(), self.clock.unix_time())
+    }
+
+    pub fn reject_order(&mut self) {
+        if let Some(executor) = &mut self.executor {
+            executor.reject();
+        }
+    }
+
+    /// Engages or releases the kill switch which stops all orders.
+    pub fn set_kill_switch(&mut self, killed: bool) {
+        if let Some(executor) = &mut self.executor {
+            executor.set_killed(killed);
+        }
+    }
+
+    pub fn set_proximity(&mut self, proximity: Proximity) {
+        self.zone.set_proximity(proximity);
+    }
+
+    pub fn reject_suggestion(&mut self, index: usize) {
+        if index < self.suggestions.len() {
+            self.suggestions.remove(index);
+        }
+    }
+
+    pub fn suggestions(&self) -> &[SuggestedZone] {
+        &self.suggestions
+    }
+
+    pub fn detection_state(&self) -> &DetectionState {
+        &self.detection
+    }
+
+    /// Writes every alert to the `log` once its outcome is known.
+    pub fn log_alerts(&mut self, log: AlertLog) {
+        self.alert_log = Some(log);
+    }
+
+    /// Follows the price after each alert and logs the alerts
+    /// whose outcome became known.
+    fn track_alerts(&mut self) {
+        let now = self.clock.now();
+        let price = self.get_price();
+        let mut settled = Vec::new();
+        self.unlogged_alerts.retain(|&i| {
+            let alert = &mut self.alerts[i];
+            alert.track(price, now);
+            if alert.outcome().is_some() {
+                settled.push(i);
+            }
+            alert.outcome().is_none()
+        });
+        for i in settled {
+            self.write_alert(i);
+        }
+    }
+
+    /// Stops reading the prices and logs the pending alerts.
+    pub fn shut_down(&mut self) {
+        if let Some(feed) = &self.feed {
+            feed.stop();
+        }
+        self.flush_alert_log();
+    }
+
+    /// Logs the alerts whose outcome is still unknown.
+    /// Should be called before the bot stops.
+    pub fn flush_alert_log(&mut self) {
+        for i in std::mem::take(&mut self.unlogged_alerts) {
+            self.write_alert(i);
+        }
+    }
+
+    fn write_alert(&mut self, index: usize) {
+        let Some(log) = &mut self.alert_log else {
+            return;
+        };
+        let alert = &self.alerts[index];
+        if !alert.notifies(AlertSink::Log) {
+            return;
+        }
+        let age = self.clock.elapsed(alert.time_created());
+        let time = self.clock.unix_time().saturating_sub(age.as_secs());
+        let record = AlertRecord::new(alert, self.symbol.as_str(), time);
+        // Stop logging if the file can't be written to anymore
+        // instead of stopping the bot.
+        if log.append(&record).is_err() {
+            self.alert_log = None;
+        }
+    }
+
+    /// Records every price update to the `recorder` from now on.
+    pub fn record_session(&mut self, recorder: SessionRecorder) {
+        self.recorder = Some(recorder);
+    }
 
     pub fn tick(&mut self) {
-        self.price_tracker.track();
+        let book = self.read_events();
+        let price = self.price_tracker.get_price();
+        self.zone.track_price(price);
+        self.zone.update_closest(price);
+        self.check_divergence();
+        if let Some(book) = book {
+            self.track_depth(book);
+        }
+        self.track_alerts();
+        self.paper.track(price);
+        if let Some(executor) = &mut self.executor {
+            executor.track(price, self.clock.unix_time());
+        }
+        self.check_detection();
         self.tick += 1;
 
-        if self.tick >= Self::UPDATE_TICKS {
+        if self.tick >= self.analysis_ticks {
             self.analyze();
             self.tick = 0;
         }
     }
 
+    /// Takes the events received since the last tick. Only the latest
+    /// prices and order book of the symbol count, the failed reads keep
+    /// the previous ones. Returns the new order book if there is one.
+    fn read_events(&mut self) -> Option<OrderBook> {
+        let venue = self.venue.kind();
+        let other = self.comparison.as_ref().map(|c| c.venue);
+        let (mut price, mut other_price, mut book) = (None, None, None);
+        for event in self.events.try_iter() {
+            match event {
+                MarketEvent::Price(kind, Ok(ticker))
+                    if ticker.symbol == self.symbol =>
+                {
+                    if kind == venue {
+                        price = Some(ticker.price);
+                    } else if Some(kind) == other {
+                        other_price = Some(ticker.price);
+                    }
+                }
+                MarketEvent::Depth(symbol, Ok(next))
+                    if symbol == self.symbol =>
+                {
+                    book = Some(next);
+                }
+                _ => (),
+            }
+        }
+        if let Some(price) = price {
+            self.price_tracker.track(price);
+            self.record_price(price);
+        }
+        if let (Some(comparison), Some(price)) =
+            (&mut self.comparison, other_price)
+        {
+            comparison.tracker.track(price);
+        }
+        book
+    }
+
+    fn record_price(&mut self, price: Price) {
+        if let Some(recorder) = &mut self.recorder {
+            let now = self.clock.now();
+            // Stop recording if the file can't be written to anymore
+            // instead of stopping the bot.
+            if recorder.record(now, &self.symbol, price).is_err() {
+                self.recorder = None;
+            }
+        }
+    }
+
     // TODO maybe do inlining
     pub fn get_price(&self) -> PriceLevel {
         self.price_tracker.get_price()
     }
 
-    pub fn get_symbol(&self) -> Symbol {
-        self.symbol
+    /// Returns the price from the tick before the latest one.
+    pub fn get_previous_price(&self) -> PriceLevel {
+        self.price_tracker.get_previous_price()
+    }
+
+    /// Returns the prices from the last few minutes, oldest first.
+    pub fn price_history(&self) -> &VecDeque<Price> {
+        self.price_tracker.history()
+    }
+
+    pub fn get_symbol(&self) -> &Symbol {
+        &self.symbol
+    }
+
+    /// Trading rules of the symbol, `None` if the exchange info
+    /// is not available.
+    pub fn symbol_info(&self) -> Option<&SymbolInfo> {
+        self.symbol_info.as_ref()
+    }
+
+    pub fn zone_strat(&self) -> &ZoneStrat {
+        &self.zone
+    }
+
+    pub fn alerts(&self) -> &[Alert] {
+        &self.alerts
+    }
+
+    pub fn paper_trader(&self) -> &PaperTrader {
+        &self.paper
     }
+
+    pub fn executor(&self) -> Option<&Executor> {
+        self.executor.as_ref()
+    }
+
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+}
+
+/// Progress of the zone detection.
+#[derive(Debug)]
+pub enum DetectionState {
+    Idle,
+    Running(Receiver<Result<Vec<SuggestedZone>, String>>),
+    Failed(String),
 }
 
+/// Price of the traded symbol on another venue.
+struct Comparison {
+    watch: DivergenceWatch,
+    venue: VenueKind,
+    tracker: PriceTracker,
+}
+
+/// Latest order book of the traded symbol.
+struct DepthTracker {
+    /// `None` until the first book is read.
+    book: Option<OrderBook>,
+    watch: DepthWatch,
+}
+
+/// Latest prices of the traded symbol.
 struct PriceTracker {
-    price: PriceLevel,
-    reader: Receiver<BinanceResult<SymbolPrice>>,
+    /// `None` until the first price is read.
+    price: Option<Price>,
+    previous_price: Option<Price>,
+    /// Prices from the last [`PriceTracker::HISTORY_CAPACITY`] ticks.
+    history: VecDeque<Price>,
 }
 
 impl PriceTracker {
-    fn new(market: Arc<Market>, symbol: Symbol) -> Self {
-        let reader = Self::spawn_price_reader(market, symbol);
+    /// Holds 5 minutes of prices when ticking every 2 seconds.
+    const HISTORY_CAPACITY: usize = 150;
+
+    fn new() -> Self {
         Self {
-            price: PriceLevel::NAN,
-            reader,
+            price: None,
+            previous_price: None,
+            history: VecDeque::with_capacity(Self::HISTORY_CAPACITY),
         }
     }
 
-    fn track(&mut self) {
-        if let Some(price) = self.reader.try_iter().last() {
-            // TODO add Into impl for PriceLevel from SymbolPrice
-            self.price = price.unwrap().into();
+    /// Forgets the prices of the previous symbol.
+    fn reset(&mut self) {
+        self.price = None;
+        self.previous_price = None;
+        self.history.clear();
+    }
+
+    /// Stores the latest price of the tick.
+    fn track(&mut self, price: Price) {
+        self.previous_price = self.price;
+        self.price = Some(price);
+
+        if self.history.len() == Self::HISTORY_CAPACITY {
+            self.history.pop_front();
         }
+        self.history.push_back(price);
     }
 
     fn get_price(&self) -> PriceLevel {
-        self.price
-    }
-
-    /// Reading the price from Binance charts blocks the thread for a short period of time
-    /// which can sometimes delay the user input so a new thread is needed.
-    ///
-    /// Every [`crate::TICK_INTERVAL`] this thread reads the market price and sends it to
-    /// the main thread which stores it in the next [`crate::TICK_INTERVAL`].
-    ///
-    /// If the price reader thread looses connection with the main thread it will just exit
-    /// and the main thread will probably just spawn a new one.
-    fn spawn_price_reader(
-        market: Arc<Market>,
-        symbol: Symbol,
-    ) -> Receiver<BinanceResult<SymbolPrice>> {
-        let (tx, rx) = channel();
-
-        thread::spawn(move || loop {
-            let price = market.get_price(symbol);
-            match tx.send(price) {
-                Ok(_) => thread::sleep(crate::TICK_INTERVAL),
-                Err(_) => break,
-            }
-        });
-        rx
+        self.price.map_or(PriceLevel::NAN, Price::level)
     }
-}
-
-#[derive(Debug, Clone, Copy)]
-pub struct Symbol(pub &'static str);
 
-impl Into<Symbol> for &'static str {
-    fn into(self) -> Symbol {
-        Symbol(self)
+    fn get_previous_price(&self) -> PriceLevel {
+        self.previous_price.map_or(PriceLevel::NAN, Price::level)
     }
-}
 
-impl Into<String> for Symbol {
-    fn into(self) -> String {
-        self.0.to_owned()
+    fn history(&self) -> &VecDeque<Price> {
+        &self.history
     }
 }
 
@@ -139,10 +791,10 @@ pub struct PriceLevel(pub f64);
 
 impl PriceLevel {
     pub const NAN: PriceLevel = PriceLevel(f64::NAN);
-}
 
-impl Into<PriceLevel> for SymbolPrice {
-    fn into(self) -> PriceLevel {
-        PriceLevel(self.price)
+    /// Percentage distance from `self` to the `other` price level.
+    /// Positive if `other` is above `self`.
+    pub fn distance_percent(self, other: PriceLevel) -> f64 {
+        (other.0 - self.0) / self.0 * 100.0
     }
 }
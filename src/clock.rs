@@ -0,0 +1,173 @@
+#[cfg(test)]
+use std::sync::{Condvar, Mutex};
+use std::{
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Source of time for the main loop, alerts and the price reader thread.
+/// Swapping the [`SystemClock`] for the [`ScaledClock`], or for the manual
+/// clock in the tests, lets the bot run on simulated time.
+pub trait Clock: Send + Sync {
+    /// Current point in time of this clock.
+    fn now(&self) -> Instant;
+
+    /// Blocks the current thread until `duration` passes on this clock.
+    fn sleep(&self, duration: Duration);
+
+    /// Time elapsed on this clock since `earlier`.
+    fn elapsed(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+
+    /// Real time it takes for `duration` to pass on this clock.
+    fn real_duration(&self, duration: Duration) -> Duration {
+        duration
+    }
+
+    /// Unix time in seconds on this clock. Clocks running ahead of
+    /// or behind the real time shift the system time accordingly.
+    fn unix_time(&self) -> u64 {
+        let (now, real) = (self.now(), Instant::now());
+        let time = if now >= real {
+            SystemTime::now().checked_add(now - real)
+        } else {
+            SystemTime::now().checked_sub(real - now)
+        };
+        time.and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |t| t.as_secs())
+    }
+}
+
+/// Clock which follows the real time.
+#[derive(Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// Clock which runs `speed` times faster than the real time.
+/// Used for fast-forwarding recorded sessions.
+#[derive(Debug)]
+pub struct ScaledClock {
+    start: Instant,
+    speed: f64,
+}
+
+impl ScaledClock {
+    pub fn new(speed: f64) -> Self {
+        assert!(speed > 0.0, "Clock speed must be positive");
+        Self {
+            start: Instant::now(),
+            speed,
+        }
+    }
+}
+
+impl Clock for ScaledClock {
+    fn now(&self) -> Instant {
+        self.start + self.start.elapsed().mul_f64(self.speed)
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(self.real_duration(duration));
+    }
+
+    fn real_duration(&self, duration: Duration) -> Duration {
+        duration.div_f64(self.speed)
+    }
+}
+
+/// Clock which moves only when [`ManualClock::advance`] is called.
+/// Threads sleeping on it wake up once enough simulated time has passed.
+#[cfg(test)]
+#[derive(Debug)]
+pub struct ManualClock {
+    start: Instant,
+    elapsed: Mutex<Duration>,
+    advanced: Condvar,
+}
+
+#[cfg(test)]
+impl ManualClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Mutex::new(Duration::ZERO),
+            advanced: Condvar::new(),
+        }
+    }
+
+    /// Moves the clock forward and wakes up all sleeping threads.
+    pub fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap() += duration;
+        self.advanced.notify_all();
+    }
+}
+
+#[cfg(test)]
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + *self.elapsed.lock().unwrap()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        let elapsed = self.elapsed.lock().unwrap();
+        let wake_at = *elapsed + duration;
+        let _elapsed = self
+            .advanced
+            .wait_while(elapsed, |elapsed| *elapsed < wake_at)
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread, time::Duration};
+
+    use super::{Clock, ManualClock};
+
+    #[test]
+    fn manual_clock_moves_only_when_advanced() {
+        let clock = ManualClock::new();
+        let start = clock.now();
+
+        assert_eq!(clock.elapsed(start), Duration::ZERO);
+        clock.advance(Duration::from_secs(300));
+        assert_eq!(clock.elapsed(start), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn sleeping_thread_wakes_on_simulated_time() {
+        let clock = Arc::new(ManualClock::new());
+        let start = clock.now();
+
+        let sleeper = {
+            let clock = clock.clone();
+            thread::spawn(move || {
+                clock.sleep(Duration::from_secs(10));
+                clock.elapsed(start)
+            })
+        };
+
+        while !sleeper.is_finished() {
+            clock.advance(Duration::from_secs(1));
+            thread::yield_now();
+        }
+        assert!(sleeper.join().unwrap() >= Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,527 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use serde::{Deserialize, Deserializer};
+use tui::style::Color;
+
+use crate::{
+    alert::{AlertRules, AlertSink},
+    bot::MarketBot,
+    divergence::DivergenceWatch,
+    strategy::{AlertDefaults, ZoneStrat},
+    venue::VenueKind,
+};
+
+/// Settings read from the TOML config file. Missing keys keep their
+/// defaults, unknown keys are an error so the typos don't go unnoticed.
+///
+/// ```toml
+/// [market]
+/// venue = "binance"
+/// symbol = "ETHUSDT"
+/// tick_interval_ms = 2000
+/// analysis_ticks = 5
+/// stream = false
+///
+/// [divergence]
+/// venue = "kraken"
+/// threshold_percent = 0.5
+///
+/// [alerts]
+/// cooldown_secs = 300
+/// sinks = ["Panel", "Log", "Bell"]
+///
+/// [ui]
+/// left_percent = 30
+/// alert_lines = 10
+///
+/// [theme]
+/// high = "red"
+/// focus = "#00d7ff"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub market: MarketConfig,
+    pub divergence: DivergenceConfig,
+    pub alerts: AlertConfig,
+    pub ui: UiConfig,
+    pub theme: Theme,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MarketConfig {
+    /// Exchange the prices and the candles are read from.
+    pub venue: VenueKind,
+    /// Pair traded when no `--symbol` is given. Only read at the start,
+    /// the `symbol` command switches the pair at runtime.
+    pub symbol: String,
+    /// How often the price is read.
+    pub tick_interval_ms: u64,
+    /// The price is analyzed once per this many ticks.
+    pub analysis_ticks: u16,
+    /// Streams the prices from the venue instead of polling them once per
+    /// tick. Only Binance streams the prices. Only read at the start.
+    pub stream: bool,
+}
+
+impl Default for MarketConfig {
+    fn default() -> Self {
+        Self {
+            venue: VenueKind::default(),
+            symbol: String::from("ETHUSDT"),
+            tick_interval_ms: MarketBot::DEFAULT_TICK_INTERVAL.as_millis()
+                as u64,
+            analysis_ticks: MarketBot::DEFAULT_ANALYSIS_TICKS,
+            stream: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DivergenceConfig {
+    /// Venue the price is compared with, `None` turns the divergence
+    /// alerts off. Only read at the start.
+    pub venue: Option<VenueKind>,
+    /// Difference of the prices in percent which raises the alert.
+    pub threshold_percent: f64,
+}
+
+impl Default for DivergenceConfig {
+    fn default() -> Self {
+        Self {
+            venue: None,
+            threshold_percent: DivergenceWatch::DEFAULT_THRESHOLD,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertConfig {
+    /// Minimum time between two alerts of the same zone.
+    pub cooldown_secs: u64,
+    /// Where the alerts of the zones without their own sinks are delivered.
+    pub sinks: Vec<AlertSink>,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            cooldown_secs: ZoneStrat::ALERT_COOLDOWN.as_secs(),
+            sinks: AlertRules::DEFAULT_SINKS.to_vec(),
+        }
+    }
+}
+
+impl AlertConfig {
+    pub fn defaults(&self) -> AlertDefaults {
+        AlertDefaults {
+            cooldown: Duration::from_secs(self.cooldown_secs),
+            sinks: self.sinks.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UiConfig {
+    /// Width of the column with the zone list in percent of the terminal.
+    pub left_percent: u16,
+    /// Height of the alert list including its borders.
+    pub alert_lines: u16,
+    /// How long to wait for the next event of the terminal resize batch.
+    pub resize_wait_ms: u64,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            left_percent: 30,
+            alert_lines: 10,
+            resize_wait_ms: 100,
+        }
+    }
+}
+
+/// Colors of the UI. Colors are written as names, e.g. `"light red"`,
+/// or as `"#rrggbb"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    #[serde(deserialize_with = "color")]
+    pub high: Color,
+    #[serde(deserialize_with = "color")]
+    pub medium: Color,
+    #[serde(deserialize_with = "color")]
+    pub low: Color,
+    /// Borders of the list the keys control.
+    #[serde(deserialize_with = "color")]
+    pub focus: Color,
+    /// Rising price, buys and profits.
+    #[serde(deserialize_with = "color")]
+    pub up: Color,
+    /// Falling price, sells and losses.
+    #[serde(deserialize_with = "color")]
+    pub down: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            high: Color::Red,
+            medium: Color::Yellow,
+            low: Color::Blue,
+            focus: Color::Cyan,
+            up: Color::Green,
+            down: Color::Red,
+        }
+    }
+}
+
+fn color<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Color, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    parse_color(&name).ok_or_else(|| {
+        serde::de::Error::custom(format!(
+            "invalid color `{name}`, expected a name like `red` or `#rrggbb`"
+        ))
+    })
+}
+
+fn parse_color(name: &str) -> Option<Color> {
+    if let Some(hex) = name.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel =
+            |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+    }
+    let name: String = name
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '-'))
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    Some(match name.as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return None,
+    })
+}
+
+impl Config {
+    /// Parses the content of the config file, applies the `overrides`
+    /// and validates the result.
+    pub fn parse(content: &str, overrides: &Overrides) -> Result<Self, String> {
+        let mut config: Config =
+            toml::from_str(content).map_err(|e| e.to_string())?;
+        overrides.apply(&mut config);
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reads the config file. A missing file means the default config.
+    pub fn load<P: AsRef<Path>>(
+        path: P,
+        overrides: &Overrides,
+    ) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content, overrides),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                Self::parse("", overrides)
+            }
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// Checks the values the TOML types can't restrict.
+    fn validate(&self) -> Result<(), String> {
+        let market = &self.market;
+        if market.symbol.trim().is_empty() {
+            return Err(String::from("market.symbol can't be empty"));
+        }
+        if market.tick_interval_ms < 100 {
+            return Err(format!(
+                "market.tick_interval_ms must be at least 100, got {}",
+                market.tick_interval_ms
+            ));
+        }
+        if market.analysis_ticks == 0 {
+            return Err(String::from("market.analysis_ticks can't be 0"));
+        }
+        if market.stream && market.venue.stream().is_none() {
+            return Err(format!(
+                "market.stream is not supported by {}",
+                market.venue
+            ));
+        }
+        let divergence = &self.divergence;
+        if divergence.venue == Some(market.venue) {
+            return Err(format!(
+                "divergence.venue must differ from market.venue, got {}",
+                market.venue
+            ));
+        }
+        let threshold = divergence.threshold_percent;
+        if !(threshold > 0.0 && threshold.is_finite()) {
+            return Err(format!(
+                "divergence.threshold_percent must be positive, got {threshold}"
+            ));
+        }
+        if !(10..=90).contains(&self.ui.left_percent) {
+            return Err(format!(
+                "ui.left_percent must be between 10 and 90, got {}",
+                self.ui.left_percent
+            ));
+        }
+        if self.ui.alert_lines < 3 {
+            return Err(format!(
+                "ui.alert_lines must be at least 3, got {}",
+                self.ui.alert_lines
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn tick_interval(&self) -> Duration {
+        Duration::from_millis(self.market.tick_interval_ms)
+    }
+
+    pub fn resize_wait(&self) -> Duration {
+        Duration::from_millis(self.ui.resize_wait_ms)
+    }
+}
+
+/// Command line options which take precedence over the config file.
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    pub venue: Option<VenueKind>,
+    pub symbol: Option<String>,
+    pub tick_interval_ms: Option<u64>,
+}
+
+impl Overrides {
+    fn apply(&self, config: &mut Config) {
+        if let Some(venue) = self.venue {
+            config.market.venue = venue;
+        }
+        if let Some(symbol) = &self.symbol {
+            config.market.symbol = symbol.clone();
+        }
+        if let Some(interval) = self.tick_interval_ms {
+            config.market.tick_interval_ms = interval;
+        }
+    }
+}
+
+/// Holds the config and reads it again when the file changes.
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    path: PathBuf,
+    overrides: Overrides,
+    /// Modification time of the file when it was last read,
+    /// `None` if the file doesn't exist.
+    modified: Option<SystemTime>,
+    config: Config,
+}
+
+impl ConfigWatcher {
+    /// Reads the config from the `path` and starts watching it.
+    pub fn load<P: Into<PathBuf>>(
+        path: P,
+        overrides: Overrides,
+    ) -> Result<Self, String> {
+        let path = path.into();
+        let modified = Self::modified(&path);
+        let config = Config::load(&path, &overrides)?;
+        Ok(Self {
+            path,
+            overrides,
+            modified,
+            config,
+        })
+    }
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
+    /// The last valid config.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Reads the config again if the file changed since the last call.
+    /// Returns `None` if it didn't change. An invalid config is reported
+    /// and the previous one stays in use. A removed file means
+    /// the default config.
+    pub fn poll(&mut self) -> Option<Result<&Config, String>> {
+        let modified = Self::modified(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(match Config::load(&self.path, &self.overrides) {
+            Ok(config) => {
+                self.config = config;
+                Ok(&self.config)
+            }
+            Err(e) => Err(e),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        time::{Duration, SystemTime},
+    };
+
+    use tui::style::Color;
+
+    use super::{Config, ConfigWatcher, Overrides};
+    use crate::{alert::AlertSink, venue::VenueKind};
+
+    #[test]
+    fn config_overrides_only_given_keys() {
+        let parse = |content| Config::parse(content, &Overrides::default());
+        let config = parse(
+            r##"
+            [market]
+            tick_interval_ms = 500
+
+            [alerts]
+            cooldown_secs = 60
+            sinks = ["Panel", "Bell"]
+
+            [theme]
+            high = "light magenta"
+            focus = "#00d7ff"
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(config.market.symbol, "ETHUSDT");
+        assert_eq!(config.tick_interval(), Duration::from_millis(500));
+        assert_eq!(config.market.analysis_ticks, 5);
+        let defaults = config.alerts.defaults();
+        assert_eq!(defaults.cooldown, Duration::from_secs(60));
+        assert_eq!(defaults.sinks, [AlertSink::Panel, AlertSink::Bell]);
+        assert_eq!(config.theme.high, Color::LightMagenta);
+        assert_eq!(config.theme.focus, Color::Rgb(0, 0xd7, 0xff));
+        assert_eq!(config.theme.low, Color::Blue);
+        assert_eq!(config.ui.left_percent, 30);
+        assert_eq!(parse("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn invalid_config_is_explained() {
+        let error = |content: &str| {
+            Config::parse(content, &Overrides::default()).unwrap_err()
+        };
+
+        assert!(error("[market]\ntick_interval = 500")
+            .contains("unknown field `tick_interval`"));
+        assert!(
+            error("[theme]\nhigh = \"reed\"").contains("invalid color `reed`")
+        );
+        assert!(error("[alerts]\nsinks = [\"Email\"]")
+            .contains("unknown variant `Email`"));
+        assert!(error("[market]\nvenue = \"coinbase\"")
+            .contains("unknown variant `coinbase`"));
+        assert_eq!(
+            error("[market]\ntick_interval_ms = 10"),
+            "market.tick_interval_ms must be at least 100, got 10"
+        );
+        assert_eq!(
+            error("[market]\nvenue = \"bybit\"\nstream = true"),
+            "market.stream is not supported by bybit"
+        );
+        assert_eq!(
+            error("[divergence]\nvenue = \"binance\""),
+            "divergence.venue must differ from market.venue, got binance"
+        );
+        assert_eq!(
+            error("[divergence]\nthreshold_percent = 0.0"),
+            "divergence.threshold_percent must be positive, got 0"
+        );
+        assert_eq!(
+            error("[ui]\nleft_percent = 95"),
+            "ui.left_percent must be between 10 and 90, got 95"
+        );
+    }
+
+    #[test]
+    fn command_line_overrides_the_file() {
+        let overrides = Overrides {
+            venue: None,
+            symbol: Some(String::from("BTCUSDT")),
+            tick_interval_ms: Some(1000),
+        };
+        let content = "[market]\nvenue = \"kraken\"\nsymbol = \"SOLUSDT\"\n\
+                       tick_interval_ms = 5000";
+        let config = Config::parse(content, &overrides).unwrap();
+        assert_eq!(config.market.venue, VenueKind::Kraken);
+        assert_eq!(config.market.symbol, "BTCUSDT");
+        assert_eq!(config.tick_interval(), Duration::from_secs(1));
+
+        let overrides = Overrides {
+            venue: Some(VenueKind::Bybit),
+            ..Overrides::default()
+        };
+        let config = Config::parse(content, &overrides).unwrap();
+        assert_eq!(config.market.venue, VenueKind::Bybit);
+
+        let overrides = Overrides {
+            tick_interval_ms: Some(50),
+            ..Overrides::default()
+        };
+        assert!(Config::parse(content, &overrides).is_err());
+    }
+
+    #[test]
+    fn watcher_keeps_last_valid_config() {
+        let path = std::env::temp_dir()
+            .join(format!("zone-bot-config-test-{}.toml", std::process::id()));
+        fs::write(&path, "[market]\nanalysis_ticks = 3\n").unwrap();
+        let mut watcher =
+            ConfigWatcher::load(&path, Overrides::default()).unwrap();
+        assert!(watcher.poll().is_none());
+
+        fs::write(&path, "[market]\nanalysis_ticks = 0\n").unwrap();
+        // Move the modification time so the change is seen even on file
+        // systems with a coarse timestamp.
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH).unwrap();
+        let reloaded = watcher.poll().unwrap().cloned();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(reloaded.unwrap_err(), "market.analysis_ticks can't be 0");
+        assert_eq!(watcher.config().market.analysis_ticks, 3);
+        // Removed file means the defaults.
+        assert_eq!(watcher.poll().unwrap().unwrap(), &Config::default());
+    }
+}
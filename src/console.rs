@@ -1,11 +1,13 @@
-use std::io;
+use std::io::{self, Write};
 
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyCode, KeyEvent};
 use tui::{backend::Backend, terminal::CompletedFrame, Terminal};
 
 use crate::{
     bot::MarketBot,
+    config::{Theme, UiConfig},
     input::{InputHandler, Interruption},
+    price::Price,
     ui::UI,
 };
 
@@ -21,7 +23,7 @@ pub struct Console<B: Backend> {
 
 impl<B: Backend> Console<B> {
     pub fn new(terminal: Terminal<B>) -> Self {
-        Self {
+        let mut console = Self {
             terminal,
             ui: UI::init(),
 
@@ -29,25 +31,86 @@ impl<B: Backend> Console<B> {
             input_mode: InputMode::Editing,
 
             exit: false,
+        };
+        // Position the UI objects before the first resize event comes in.
+        console.resize();
+        console
+    }
+
+    /// In the control mode keys select and manage the suggested zones,
+    /// select the zone for the risk calculator
+    /// and confirm or reject the orders.
+    pub fn process_controls(&mut self, event: KeyEvent) -> Option<Command> {
+        match event.code {
+            KeyCode::Tab | KeyCode::Esc => {
+                self.set_input_mode(InputMode::Editing)
+            }
+            KeyCode::Up => self.ui.select(false),
+            KeyCode::Down => self.ui.select(true),
+            KeyCode::Char('z') => self.ui.switch_focus(),
+            KeyCode::Char('a') => {
+                return self
+                    .ui
+                    .selected_suggestion()
+                    .map(Command::AcceptSuggestion)
+            }
+            KeyCode::Char('r') => {
+                return self
+                    .ui
+                    .selected_suggestion()
+                    .map(Command::RejectSuggestion)
+            }
+            KeyCode::Char('y') => return Some(Command::ConfirmOrder),
+            KeyCode::Char('n') => return Some(Command::RejectOrder),
+            _ => (),
         }
+        None
     }
 
-    pub fn process_controls(&mut self, event: KeyEvent) {}
+    pub fn process_editing(&mut self, event: KeyEvent) -> Option<Command> {
+        if event.code == KeyCode::Tab {
+            self.set_input_mode(InputMode::Control);
+            return None;
+        }
 
-    pub fn process_editing(&mut self, event: KeyEvent) {
+        let mut command = None;
         if let Some(interruption) = self.input.process_input(event) {
             match interruption {
-                Interruption::Enter(buf) => println!("You entered: {buf}"),
+                Interruption::Enter(buf) => match Command::parse(&buf) {
+                    Some(c) => command = Some(c),
+                    None => self
+                        .show_status(Some(format!("Unknown command: {buf}"))),
+                },
                 Interruption::Esc => self.exit = true,
             }
         }
+        self.ui.update_input(&self.input);
+        command
+    }
+
+    fn set_input_mode(&mut self, mode: InputMode) {
+        self.input_mode = mode;
+        self.ui.update_input_mode(mode);
+    }
+
+    /// Shows the result of the last command, `None` clears it.
+    pub fn show_status(&mut self, status: Option<String>) {
+        self.ui.update_status(status);
+    }
+
+    /// Lays the UI out with the `layout` sizes and colors it
+    /// with the `theme`.
+    pub fn configure(&mut self, layout: UiConfig, theme: Theme) {
+        self.ui.set_layout(layout);
+        self.ui.set_theme(theme);
+        self.resize();
     }
 
     pub fn update_ui(&mut self, data: &MarketBot) {
         self.ui.update(data);
     }
 
-    pub fn render_ui(&mut self) -> io::Result<CompletedFrame> {
+    pub fn render_ui(&mut self) -> io::Result<CompletedFrame<'_>> {
         self.terminal.draw(|f| self.ui.render(f))
     }
 
@@ -65,8 +128,588 @@ impl<B: Backend> Console<B> {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+impl<B: Backend + Write> Console<B> {
+    /// Rings the terminal bell through the backend so it doesn't get
+    /// in the way of the drawn frames.
+    pub fn ring_bell(&mut self) -> io::Result<()> {
+        let backend = self.terminal.backend_mut();
+        backend.write_all(b"\x07")?;
+        Write::flush(backend)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InputMode {
     Editing,
     Control,
 }
+
+/// Actions requested by the user which change the bot.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Detect zones from the historical prices.
+    DetectZones,
+    AcceptSuggestion(usize),
+    RejectSuggestion(usize),
+    /// Move the zone at the index to the new low and high.
+    EditZone(usize, Price, Price),
+    /// Place the order waiting for the confirmation.
+    ConfirmOrder,
+    RejectOrder,
+    /// Engage or release the kill switch which stops all orders.
+    KillSwitch(bool),
+    /// List the symbols matching the query.
+    FindSymbol(String),
+    /// Trade another symbol.
+    SwitchSymbol(String),
+}
+
+impl Command {
+    /// Parses the command typed into the input box.
+    fn parse(input: &str) -> Option<Self> {
+        let mut words = input.split_whitespace();
+        match (words.next()?, words.next(), words.next(), words.next()) {
+            ("detect", None, ..) => Some(Command::DetectZones),
+            ("kill", None, ..) => Some(Command::KillSwitch(true)),
+            ("resume", None, ..) => Some(Command::KillSwitch(false)),
+            ("find", Some(query), None, ..) => {
+                Some(Command::FindSymbol(query.to_owned()))
+            }
+            ("symbol", Some(symbol), None, ..) => {
+                Some(Command::SwitchSymbol(symbol.to_owned()))
+            }
+            // Zones are numbered from 1 in the Zone List.
+            ("edit", Some(zone), Some(low), Some(high)) => {
+                let zone = zone.parse::<usize>().ok()?.checked_sub(1)?;
+                let (low, high) = (low.parse().ok()?, high.parse().ok()?);
+                words
+                    .next()
+                    .is_none()
+                    .then_some(Command::EditZone(zone, low, high))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            mpsc::{channel, Sender},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use tui::{backend::TestBackend, buffer::Buffer, Terminal};
+
+    use super::{Command, Console};
+    use crate::{
+        alert::Position,
+        analysis::Candle,
+        bot::MarketBot,
+        clock::ManualClock,
+        config::{Config, Overrides},
+        depth::{self, OrderBook},
+        execution::{ExecutionConfig, Executor},
+        feed::{MarketEvent, PriceSource, Ticker},
+        price::Price,
+        save::AlertRecord,
+        strategy::{Zone, ZonePriority},
+        symbol::{Symbol, SymbolInfo},
+        venue::{Venue, VenueKind},
+    };
+
+    const WIDTH: u16 = 100;
+    const HEIGHT: u16 = 30;
+
+    fn console() -> Console<TestBackend> {
+        let backend = TestBackend::new(WIDTH, HEIGHT);
+        Console::new(Terminal::new(backend).unwrap())
+    }
+
+    type EventSender = Sender<MarketEvent>;
+
+    /// Venue the tests never reach, the prices come from the senders.
+    struct OfflineVenue;
+
+    impl PriceSource for OfflineVenue {
+        fn prices(&self, _: &[Symbol]) -> Result<Vec<Ticker>, String> {
+            Err(String::from("offline"))
+        }
+    }
+
+    impl Venue for OfflineVenue {
+        fn kind(&self) -> VenueKind {
+            VenueKind::Binance
+        }
+
+        fn candles(
+            &self,
+            _: &Symbol,
+            _: u32,
+            _: u16,
+        ) -> Result<Vec<Candle>, String> {
+            Err(String::from("offline"))
+        }
+
+        fn depth(&self, _: &Symbol, _: u16) -> Result<OrderBook, String> {
+            Err(String::from("offline"))
+        }
+    }
+
+    fn bot_with_clock(
+        zones: Vec<Zone>,
+        clock: Arc<ManualClock>,
+    ) -> (MarketBot, EventSender) {
+        let (tx, rx) = channel();
+        let venue = Arc::new(OfflineVenue);
+        (
+            MarketBot::with_price_reader("ETHUSDT", zones, rx, venue, clock),
+            tx,
+        )
+    }
+
+    fn bot(zones: Vec<Zone>) -> (MarketBot, EventSender) {
+        bot_with_clock(zones, Arc::new(ManualClock::new()))
+    }
+
+    fn send_price(tx: &EventSender, price: f64) {
+        send_venue_price(tx, VenueKind::Binance, price);
+    }
+
+    fn send_venue_price(tx: &EventSender, venue: VenueKind, price: f64) {
+        let ticker = Ticker {
+            symbol: Symbol::new("ETHUSDT"),
+            price: Price::from_f64(price).unwrap(),
+        };
+        tx.send(MarketEvent::Price(venue, Ok(ticker))).unwrap();
+    }
+
+    fn send_book(tx: &EventSender, book: OrderBook) {
+        let symbol = Symbol::new("ETHUSDT");
+        tx.send(MarketEvent::Depth(symbol, Ok(book))).unwrap();
+    }
+
+    fn press(console: &mut Console<TestBackend>, keys: &[KeyCode]) {
+        for key in keys {
+            console.process_editing(KeyEvent::new(*key, KeyModifiers::NONE));
+        }
+    }
+
+    fn type_str(console: &mut Console<TestBackend>, input: &str) {
+        let keys: Vec<KeyCode> = input.chars().map(KeyCode::Char).collect();
+        press(console, &keys);
+    }
+
+    /// Renders the console and returns the buffer as lines of text.
+    fn render(console: &mut Console<TestBackend>) -> (Buffer, Vec<String>) {
+        let buffer = console.render_ui().unwrap().buffer.clone();
+        let lines = buffer
+            .content
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol.as_str()).collect())
+            .collect();
+        (buffer, lines)
+    }
+
+    fn contains(lines: &[String], text: &str) -> bool {
+        lines.iter().any(|line| line.contains(text))
+    }
+
+    fn zone(priority: ZonePriority, low: f64, high: f64) -> Zone {
+        let price = |p| Price::from_f64(p).unwrap();
+        Zone::new(priority, price(low), price(high))
+    }
+
+    #[test]
+    fn renders_layout_before_any_resize() {
+        let mut console = console();
+        let (_, lines) = render(&mut console);
+
+        for title in ["Commands", "Zone List", "Alerts", "Live Price", "Input"]
+        {
+            assert!(contains(&lines, title), "missing {title}");
+        }
+    }
+
+    #[test]
+    fn live_price_shows_symbol_price_and_zone_distances() {
+        let mut console = console();
+        let (mut bot, tx) = bot(vec![
+            zone(ZonePriority::High, 1100.0, 1150.0),
+            zone(ZonePriority::Low, 900.0, 950.0),
+        ]);
+
+        send_price(&tx, 1000.0);
+        bot.tick();
+        console.update_ui(&bot);
+        let (_, lines) = render(&mut console);
+
+        assert!(contains(&lines, "ETHUSDT: 1000"));
+        assert!(contains(&lines, "+10.00%"));
+        assert!(contains(&lines, "-5.00%"));
+    }
+
+    #[test]
+    fn failed_price_reads_keep_the_last_price() {
+        let mut console = console();
+        let (mut bot, tx) = bot(Vec::new());
+
+        send_price(&tx, 1000.0);
+        bot.tick();
+        let failed = Err(String::from("connection reset"));
+        tx.send(MarketEvent::Price(VenueKind::Binance, failed))
+            .unwrap();
+        bot.tick();
+        console.update_ui(&bot);
+        let (_, lines) = render(&mut console);
+
+        assert!(contains(&lines, "ETHUSDT: 1000"));
+    }
+
+    #[test]
+    fn live_price_is_colored_by_tick_direction() {
+        let mut console = console();
+        let (mut bot, tx) = bot(Vec::new());
+
+        let price_color = |console: &mut Console<TestBackend>, price: &str| {
+            let (buffer, lines) = render(console);
+            let (y, line) = lines
+                .iter()
+                .enumerate()
+                .find(|(_, line)| line.contains(price))
+                .unwrap();
+            let x = line.find(price).unwrap() as u16;
+            buffer.get(x, y as u16).fg
+        };
+
+        send_price(&tx, 1000.0);
+        bot.tick();
+        send_price(&tx, 1010.0);
+        bot.tick();
+        console.update_ui(&bot);
+        assert_eq!(price_color(&mut console, "1010"), tui::style::Color::Green);
+
+        send_price(&tx, 990.0);
+        bot.tick();
+        console.update_ui(&bot);
+        assert_eq!(price_color(&mut console, "990"), tui::style::Color::Red);
+    }
+
+    #[test]
+    fn config_changes_layout_and_colors() {
+        let mut console = console();
+        let (bot, _tx) = bot(vec![zone(ZonePriority::High, 1100.0, 1150.0)]);
+        let config = Config::parse(
+            "[ui]\nleft_percent = 50\n[theme]\nhigh = \"magenta\"",
+            &Overrides::default(),
+        )
+        .unwrap();
+
+        console.configure(config.ui, config.theme);
+        console.update_ui(&bot);
+        let (buffer, lines) = render(&mut console);
+
+        let alerts = lines.iter().find_map(|line| line.find("Alerts"));
+        assert!(alerts.unwrap() > WIDTH as usize / 2);
+        let (y, line) = lines
+            .iter()
+            .enumerate()
+            .find(|(_, line)| line.contains("High"))
+            .unwrap();
+        let x = line.find("High").unwrap() as u16;
+        assert_eq!(buffer.get(x, y as u16).fg, tui::style::Color::Magenta);
+    }
+
+    #[test]
+    fn zone_list_shows_loaded_zones() {
+        let mut console = console();
+        let (bot, _tx) = bot(vec![zone(ZonePriority::High, 1100.0, 1150.0)]);
+
+        console.update_ui(&bot);
+        let (_, lines) = render(&mut console);
+
+        assert!(contains(&lines, "High"));
+        assert!(contains(&lines, "1100 - 1150 New"));
+    }
+
+    #[test]
+    fn alert_shows_its_age_on_simulated_time() {
+        let mut console = console();
+        let clock = Arc::new(ManualClock::new());
+        let (mut bot, tx) = bot_with_clock(
+            vec![zone(ZonePriority::High, 1000.0, 1100.0)],
+            clock.clone(),
+        );
+
+        // Analysis happens every few ticks, so keep the price steady
+        // above the zone and then inside of it until both are analyzed.
+        for price in [1200.0, 1050.0] {
+            for _ in 0..5 {
+                send_price(&tx, price);
+                bot.tick();
+            }
+        }
+        clock.advance(Duration::from_secs(5 * 60));
+        console.update_ui(&bot);
+        let (_, lines) = render(&mut console);
+
+        assert!(contains(&lines, "Alert at 1050"));
+        assert!(contains(&lines, "Time: 5m 0s ago"));
+        assert!(contains(&lines, "Position: (Possible) Buy"));
+    }
+
+    #[test]
+    fn typed_input_is_rendered_and_esc_exits() {
+        let mut console = console();
+
+        type_str(&mut console, "xyz1");
+        let (_, lines) = render(&mut console);
+        assert!(contains(&lines, "xyz1"));
+
+        // First ESC clears the input, the second one exits.
+        press(&mut console, &[KeyCode::Esc]);
+        let (_, lines) = render(&mut console);
+        assert!(!contains(&lines, "xyz1"));
+        assert!(!console.should_exit());
+
+        press(&mut console, &[KeyCode::Esc]);
+        assert!(console.should_exit());
+    }
+
+    #[test]
+    fn unknown_command_shows_status() {
+        // Wide enough for the status next to the input title.
+        let backend = TestBackend::new(2 * WIDTH, HEIGHT);
+        let mut console = Console::new(Terminal::new(backend).unwrap());
+
+        type_str(&mut console, "foo 1");
+        let command = console
+            .process_editing(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(command, None);
+        let (_, lines) = render(&mut console);
+        assert!(contains(&lines, "Input - Unknown command: foo 1"));
+        // The input is cleared for the next command.
+        assert!(!lines.iter().any(|line| line.contains("│foo 1")));
+    }
+
+    #[test]
+    fn detect_command_and_control_mode() {
+        let mut console = console();
+
+        type_str(&mut console, "detect");
+        let command = console
+            .process_editing(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(command, Some(Command::DetectZones));
+
+        assert_eq!(
+            Command::parse("edit 2 1200 1250.5"),
+            Some(Command::EditZone(
+                1,
+                Price::new(1200, 0),
+                Price::new(12505, 1)
+            ))
+        );
+        assert_eq!(Command::parse("edit 0 1200 1250"), None);
+        assert_eq!(Command::parse("kill"), Some(Command::KillSwitch(true)));
+        assert_eq!(
+            Command::parse("find eth"),
+            Some(Command::FindSymbol(String::from("eth")))
+        );
+        assert_eq!(
+            Command::parse("symbol btc/usdt"),
+            Some(Command::SwitchSymbol(String::from("btc/usdt")))
+        );
+
+        press(&mut console, &[KeyCode::Tab]);
+        let (_, lines) = render(&mut console);
+        assert!(contains(&lines, "Control mode (TAB - edit)"));
+        assert!(contains(&lines, "Suggested Zones"));
+
+        // Nothing to accept without any suggestions.
+        let command = console.process_controls(KeyEvent::new(
+            KeyCode::Char('a'),
+            KeyModifiers::NONE,
+        ));
+        assert_eq!(command, None);
+    }
+
+    #[test]
+    fn selected_zone_shows_risk_plan() {
+        let mut console = console();
+        let (mut bot, tx) = bot(vec![
+            zone(ZonePriority::High, 1000.0, 1100.0),
+            zone(ZonePriority::Low, 1300.0, 1350.0),
+        ]);
+        send_price(&tx, 1200.0);
+        bot.tick();
+        console.update_ui(&bot);
+
+        press(&mut console, &[KeyCode::Tab]);
+        for key in [KeyCode::Char('z'), KeyCode::Down] {
+            console.process_controls(KeyEvent::new(key, KeyModifiers::NONE));
+        }
+        console.update_ui(&bot);
+        let (_, lines) = render(&mut console);
+
+        assert!(contains(&lines, "Risk - zone 1"));
+        assert!(contains(&lines, "Buy 1100 SL 999 TP 1300 (1.98R)"));
+        assert!(contains(&lines, "entry -8.33% from the price"));
+    }
+
+    #[test]
+    fn prices_follow_symbol_tick_size() {
+        let mut console = console();
+        let (mut bot, tx) = bot(vec![zone(ZonePriority::High, 1000.0, 1100.0)]);
+        let price = |s: &str| s.parse::<Price>().unwrap();
+        bot.set_symbol_info(SymbolInfo {
+            symbol: Symbol::new("ETHUSDT"),
+            base: String::from("ETH"),
+            quote: String::from("USDT"),
+            status: String::from("TRADING"),
+            tick_size: Some(price("0.01")),
+            lot_size: Some(price("0.0001")),
+            min_quantity: None,
+        });
+        send_price(&tx, 1200.5);
+        bot.tick();
+
+        bot.edit_zone(0, price("1000.004"), price("1100.005"))
+            .unwrap();
+        console.update_ui(&bot);
+        let (_, lines) = render(&mut console);
+
+        assert!(contains(&lines, "ETH/USDT: 1200.50"));
+        assert!(contains(&lines, "1. 1000.00 - 1100.01"));
+    }
+
+    #[test]
+    fn switching_symbol_loads_its_zones_and_alerts() {
+        let mut console = console();
+        let (mut bot, old_tx) =
+            bot(vec![zone(ZonePriority::High, 1000.0, 1100.0)]);
+        send_price(&old_tx, 1050.0);
+        bot.tick();
+
+        let record: AlertRecord = serde_json::from_str(
+            r#"{"time": 0, "symbol": "BTCUSDT", "price": "29400",
+                "position": "Buy", "cause": "Price entered Low zone",
+                "outcome": "Hit", "best_move": 1.5, "worst_move": -0.2}"#,
+        )
+        .unwrap();
+        let history = vec![record.to_alert(bot.clock().now(), 60)];
+        let (tx, rx) = channel();
+        bot.switch_symbol(
+            Symbol::new("BTCUSDT"),
+            vec![zone(ZonePriority::Low, 29000.0, 29500.0)],
+            history,
+            Some(rx),
+        )
+        .unwrap();
+        // The old reader is disconnected.
+        let stale = MarketEvent::Price(VenueKind::Binance, Err("stale".into()));
+        assert!(old_tx.send(stale).is_err());
+
+        let ticker = Ticker {
+            symbol: Symbol::new("BTCUSDT"),
+            price: Price::new(30100, 0),
+        };
+        tx.send(MarketEvent::Price(VenueKind::Binance, Ok(ticker)))
+            .unwrap();
+        bot.tick();
+        console.update_ui(&bot);
+        let (_, lines) = render(&mut console);
+
+        assert!(contains(&lines, "BTCUSDT: 30100"));
+        assert!(contains(&lines, "1. 29000 - 29500"));
+        assert!(!contains(&lines, "1000 - 1100"));
+        assert!(contains(&lines, "Alert at 29400"));
+        assert!(contains(&lines, "Price entered Low zone"));
+    }
+
+    #[test]
+    fn divergence_alert_shows_both_prices() {
+        let mut console = console();
+        let (mut bot, tx) = bot(Vec::new());
+        bot.compare_prices(VenueKind::Kraken, 0.5);
+        bot.execute_orders(Executor::new(ExecutionConfig::default(), None));
+
+        send_price(&tx, 1000.0);
+        send_venue_price(&tx, VenueKind::Kraken, 1008.0);
+        bot.tick();
+        console.update_ui(&bot);
+        let (_, lines) = render(&mut console);
+
+        assert!(contains(&lines, "Alert at 1000"));
+        assert!(contains(&lines, "Position: (Possible) Buy"));
+        assert!(contains(&lines, "Price diverged 0.80% from kraken"));
+        assert!(contains(&lines, "binance 1000 vs kraken 1008"));
+        // The divergence is not traded.
+        assert!(bot.executor().unwrap().pending().is_none());
+        assert!(bot.paper_trader().open_positions().is_empty());
+    }
+
+    #[test]
+    fn zone_list_shows_liquidity_and_wall_alert() {
+        let mut console = console();
+        // Wide enough for the liquidity after the zone state.
+        let config =
+            Config::parse("[ui]\nleft_percent = 50", &Overrides::default())
+                .unwrap();
+        console.configure(config.ui, config.theme);
+        let (mut bot, tx) = bot(vec![zone(ZonePriority::High, 1000.0, 1100.0)]);
+        bot.watch_depth();
+        let snapshots =
+            depth::load_depth("fixtures/depth_replay.jsonl").unwrap();
+
+        send_price(&tx, 1055.0);
+        send_book(&tx, snapshots[0].book.clone());
+        bot.tick();
+        send_book(&tx, snapshots[1].book.clone());
+        bot.tick();
+        console.update_ui(&bot);
+        let (_, lines) = render(&mut console);
+
+        assert!(contains(&lines, "bid 28.30 ask 3.00"));
+        assert!(contains(&lines, "Bid wall of 25 appeared at 1030"));
+    }
+
+    #[test]
+    fn opposing_walls_alert_separately() {
+        let (mut bot, tx) = bot(vec![zone(ZonePriority::High, 1000.0, 1100.0)]);
+        bot.watch_depth();
+        let snapshots =
+            depth::load_depth("fixtures/depth_replay.jsonl").unwrap();
+        // Bid wall at 1030 and ask wall at 1080 appear at once.
+        let mut walls = snapshots[1].book.clone();
+        walls.asks[2] = (1080.0, 30.0);
+
+        send_price(&tx, 1055.0);
+        send_book(&tx, snapshots[0].book.clone());
+        bot.tick();
+        send_book(&tx, walls);
+        bot.tick();
+
+        let alerts: Vec<(&str, Position)> = bot
+            .alerts()
+            .iter()
+            .map(|alert| (alert.cause(), alert.position()))
+            .collect();
+        assert_eq!(
+            alerts,
+            [
+                (
+                    "Bid wall of 25 appeared at 1030 in High zone",
+                    Position::Buy
+                ),
+                (
+                    "Ask wall of 30 appeared at 1080 in High zone",
+                    Position::Sell
+                )
+            ]
+        );
+    }
+}
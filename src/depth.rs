@@ -0,0 +1,359 @@
+use std::{
+    fs, io,
+    path::Path,
+    sync::{mpsc::Sender, Arc},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    alert::{Alert, AlertZone, Position},
+    bot::PriceLevel,
+    clock::Clock,
+    feed::MarketEvent,
+    price::Price,
+    strategy::{AlertDefaults, Zone, ZonePriority},
+    symbol::Symbol,
+};
+
+/// How often the order book is read from the venue.
+pub const DEPTH_INTERVAL: Duration = Duration::from_secs(10);
+/// Number of price levels read on each side of the book.
+pub const DEPTH_LIMIT: u16 = 100;
+/// Level holding this many times the median quantity of its side is a wall.
+const WALL_RATIO: f64 = 5.0;
+
+/// Resting orders of a symbol as `(price, quantity)` levels,
+/// the best prices first.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+pub struct OrderBook {
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+}
+
+impl OrderBook {
+    /// Quantity resting on each side between the `low` and `high` prices.
+    pub fn liquidity(&self, low: Price, high: Price) -> Liquidity {
+        let (low, high) = (low.to_f64(), high.to_f64());
+        let inside = |levels: &[(f64, f64)]| -> f64 {
+            levels
+                .iter()
+                .filter(|(price, _)| (low..=high).contains(price))
+                .map(|(_, quantity)| quantity)
+                .sum()
+        };
+        Liquidity {
+            bid: inside(&self.bids),
+            ask: inside(&self.asks),
+        }
+    }
+
+    /// Levels holding [`WALL_RATIO`] times the median quantity of their
+    /// side of the book.
+    pub fn walls(&self) -> Vec<Wall> {
+        let mut walls = side_walls(BookSide::Bid, &self.bids);
+        walls.extend(side_walls(BookSide::Ask, &self.asks));
+        walls
+    }
+}
+
+fn side_walls(side: BookSide, levels: &[(f64, f64)]) -> Vec<Wall> {
+    let mut quantities: Vec<f64> =
+        levels.iter().map(|(_, quantity)| *quantity).collect();
+    quantities.sort_by(f64::total_cmp);
+    let Some(median) = quantities.get(quantities.len() / 2) else {
+        return Vec::new();
+    };
+    levels
+        .iter()
+        .filter(|(_, quantity)| *quantity >= median * WALL_RATIO)
+        .map(|&(price, quantity)| Wall {
+            side,
+            price,
+            quantity,
+        })
+        .collect()
+}
+
+/// Quantity of the resting bids and asks inside a zone.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Liquidity {
+    pub bid: f64,
+    pub ask: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookSide {
+    Bid,
+    Ask,
+}
+
+/// Price level with much more resting quantity than the rest of the book.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wall {
+    pub side: BookSide,
+    pub price: f64,
+    pub quantity: f64,
+}
+
+impl Wall {
+    /// Whether the wall is the same level as the `other` one.
+    fn is_at(&self, other: &Wall) -> bool {
+        self.side == other.side && self.price == other.price
+    }
+}
+
+/// Compares the walls of every order book with the walls of the previous
+/// one and raises the alerts for the walls which appeared or disappeared
+/// inside the active high priority zones.
+#[derive(Debug, Default)]
+pub struct DepthWatch {
+    /// Walls of the previous book, `None` before the first book.
+    walls: Option<Vec<Wall>>,
+}
+
+impl DepthWatch {
+    /// Forgets the walls of the previous symbol.
+    pub fn reset(&mut self) {
+        self.walls = None;
+    }
+
+    /// Returns the alerts of the walls which changed since the previous
+    /// book. A bid wall appearing or an ask wall disappearing suggests
+    /// buying, the other way round selling. The first book only sets
+    /// the walls to compare with.
+    pub fn update(
+        &mut self,
+        book: &OrderBook,
+        zones: &[Zone],
+        price: PriceLevel,
+        now: Instant,
+        defaults: &AlertDefaults,
+    ) -> Vec<Alert> {
+        let walls = book.walls();
+        let Some(previous) = self.walls.replace(walls.clone()) else {
+            return Vec::new();
+        };
+        let appeared = walls
+            .iter()
+            .filter(|wall| !previous.iter().any(|p| p.is_at(wall)))
+            .map(|wall| (wall, true));
+        let disappeared = previous
+            .iter()
+            .filter(|wall| !walls.iter().any(|w| w.is_at(wall)))
+            .map(|wall| (wall, false));
+
+        let mut alerts = Vec::new();
+        for (wall, appeared) in appeared.chain(disappeared) {
+            let zone = zones.iter().find(|zone| {
+                zone.priority == ZonePriority::High
+                    && zone.is_active()
+                    && (zone.low.to_f64()..=zone.high.to_f64())
+                        .contains(&wall.price)
+            });
+            let Some(zone) = zone else {
+                continue;
+            };
+            let side = match wall.side {
+                BookSide::Bid => "Bid",
+                BookSide::Ask => "Ask",
+            };
+            let position = match (wall.side, appeared) {
+                (BookSide::Bid, true) | (BookSide::Ask, false) => Position::Buy,
+                (BookSide::Ask, true) | (BookSide::Bid, false) => {
+                    Position::Sell
+                }
+            };
+            let event = if appeared { "appeared" } else { "disappeared" };
+            let cause = format!(
+                "{side} wall of {} {event} at {} in High zone",
+                wall.quantity, wall.price
+            );
+            let alert_zone = AlertZone {
+                priority: zone.priority,
+                low: zone.low,
+                high: zone.high,
+            };
+            alerts.push(
+                Alert::new(now, price, position, cause)
+                    .with_zone(alert_zone)
+                    .with_sinks(
+                        zone.rules.sinks.as_deref().unwrap_or(&defaults.sinks),
+                    ),
+            );
+        }
+        alerts
+    }
+}
+
+/// Order book snapshot of a replay file.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct RecordedDepth {
+    /// Milliseconds since the start of the replay.
+    pub time_ms: u64,
+    #[serde(flatten)]
+    pub book: OrderBook,
+}
+
+/// Reads the order book snapshots from the file, one JSON snapshot
+/// per line.
+pub fn load_depth<P: AsRef<Path>>(path: P) -> io::Result<Vec<RecordedDepth>> {
+    let content = fs::read_to_string(path)?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid depth snapshot at line {}: {e}", i + 1),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Sends the order book snapshots of the `symbol` to the bot at their
+/// times. How fast they replay depends on the provided `clock`, the same
+/// as the replayed prices.
+pub fn spawn_depth_replay(
+    snapshots: Vec<RecordedDepth>,
+    symbol: Symbol,
+    clock: Arc<dyn Clock>,
+    tx: Sender<MarketEvent>,
+) {
+    thread::spawn(move || {
+        let mut last = Duration::ZERO;
+        for snapshot in snapshots {
+            let time = Duration::from_millis(snapshot.time_ms);
+            clock.sleep(time.saturating_sub(last));
+            last = time;
+
+            let book = MarketEvent::Depth(symbol.clone(), Ok(snapshot.book));
+            if tx.send(book).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{mpsc::channel, Arc},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use super::{
+        load_depth, spawn_depth_replay, BookSide, DepthWatch, OrderBook,
+    };
+    use crate::{
+        alert::Position,
+        bot::PriceLevel,
+        clock::ManualClock,
+        feed::MarketEvent,
+        price::Price,
+        strategy::{AlertDefaults, Zone, ZonePriority},
+        symbol::Symbol,
+    };
+
+    const REPLAY: &str = "fixtures/depth_replay.jsonl";
+
+    fn zone(priority: ZonePriority, low: f64, high: f64) -> Zone {
+        Zone::new(
+            priority,
+            Price::from_f64(low).unwrap(),
+            Price::from_f64(high).unwrap(),
+        )
+    }
+
+    #[test]
+    fn liquidity_and_walls_of_the_book() {
+        let book = OrderBook {
+            bids: vec![(1050.0, 1.0), (1040.0, 2.0), (990.0, 20.0)],
+            asks: vec![(1060.0, 1.5), (1120.0, 2.5)],
+        };
+        let liquidity = book.liquidity(
+            Price::from_f64(1000.0).unwrap(),
+            Price::from_f64(1100.0).unwrap(),
+        );
+        assert_eq!((liquidity.bid, liquidity.ask), (3.0, 1.5));
+
+        let walls = book.walls();
+        assert_eq!(walls.len(), 1);
+        assert_eq!(walls[0].side, BookSide::Bid);
+        assert_eq!(walls[0].price, 990.0);
+    }
+
+    #[test]
+    fn walls_in_high_zones_alert_when_they_change() {
+        let snapshots = load_depth(REPLAY).unwrap();
+        let zones = [
+            zone(ZonePriority::High, 1000.0, 1100.0),
+            zone(ZonePriority::Low, 1000.0, 1100.0),
+        ];
+        let mut watch = DepthWatch::default();
+        let mut update = |i: usize| {
+            watch.update(
+                &snapshots[i].book,
+                &zones,
+                PriceLevel(1055.0),
+                Instant::now(),
+                &AlertDefaults::default(),
+            )
+        };
+
+        assert!(update(0).is_empty());
+        let alerts = update(1);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(
+            alerts[0].cause(),
+            "Bid wall of 25 appeared at 1030 in High zone"
+        );
+        assert_eq!(alerts[0].position(), Position::Buy);
+        assert_eq!(alerts[0].zone().unwrap().priority, ZonePriority::High);
+
+        let causes: Vec<(String, Position)> = update(2)
+            .iter()
+            .map(|a| (a.cause().to_owned(), a.position()))
+            .collect();
+        assert_eq!(
+            causes,
+            [
+                (
+                    String::from(
+                        "Ask wall of 30 appeared at 1080 in High zone"
+                    ),
+                    Position::Sell
+                ),
+                (
+                    String::from(
+                        "Bid wall of 25 disappeared at 1030 in High zone"
+                    ),
+                    Position::Sell
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn depth_replay_follows_snapshot_times() {
+        let snapshots = load_depth(REPLAY).unwrap();
+        let clock = Arc::new(ManualClock::new());
+        let (tx, reader) = channel();
+        let eth = Symbol::new("ETHUSDT");
+        spawn_depth_replay(snapshots, eth.clone(), clock.clone(), tx);
+        let book = || match reader.recv().unwrap() {
+            MarketEvent::Depth(symbol, book) if symbol == eth => book.unwrap(),
+            event => panic!("{event:?} is not the book of {eth}"),
+        };
+
+        assert_eq!(book().bids[2], (1030.0, 0.8));
+        thread::sleep(Duration::from_millis(50));
+        assert!(reader.try_recv().is_err());
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(book().bids[2], (1030.0, 25.0));
+    }
+}
@@ -0,0 +1,144 @@
+use std::time::Instant;
+
+use crate::{
+    alert::{Alert, AlertDivergence, Position},
+    bot::PriceLevel,
+    strategy::AlertDefaults,
+    venue::VenueKind,
+};
+
+/// Compares the price of the traded pair on the bot's venue with its price
+/// on another venue. The alert is raised when the prices move apart more
+/// than the threshold and not again until they come back together.
+#[derive(Debug, Clone)]
+pub struct DivergenceWatch {
+    venue: VenueKind,
+    other_venue: VenueKind,
+    /// Difference of the prices in percent which raises the alert.
+    threshold: f64,
+    /// Whether the prices are further apart than the threshold.
+    diverged: bool,
+    last_alert: Option<Instant>,
+}
+
+impl DivergenceWatch {
+    pub const DEFAULT_THRESHOLD: f64 = 0.5;
+
+    pub fn new(
+        venue: VenueKind,
+        other_venue: VenueKind,
+        threshold: f64,
+    ) -> Self {
+        Self {
+            venue,
+            other_venue,
+            threshold,
+            diverged: false,
+            last_alert: None,
+        }
+    }
+
+    /// Forgets the divergence of the previous pair.
+    pub fn reset(&mut self) {
+        self.diverged = false;
+        self.last_alert = None;
+    }
+
+    /// Compares the `price` with the `other_price` of the other venue.
+    /// Returns the alert if the prices just moved apart and the last alert
+    /// is older than the cooldown of the `defaults`. The cheaper venue
+    /// suggests buying, the price is expected to catch up.
+    pub fn check(
+        &mut self,
+        price: PriceLevel,
+        other_price: PriceLevel,
+        now: Instant,
+        defaults: &AlertDefaults,
+    ) -> Option<Alert> {
+        if price.0.is_nan() || other_price.0.is_nan() {
+            return None;
+        }
+        let spread = price.distance_percent(other_price);
+        if spread.abs() < self.threshold {
+            self.diverged = false;
+            return None;
+        }
+        if std::mem::replace(&mut self.diverged, true) {
+            return None;
+        }
+        let cooling_down = self.last_alert.is_some_and(|t| {
+            now.saturating_duration_since(t) < defaults.cooldown
+        });
+        if cooling_down {
+            return None;
+        }
+        self.last_alert = Some(now);
+
+        let position = if spread > 0.0 {
+            Position::Buy
+        } else {
+            Position::Sell
+        };
+        let cause = format!(
+            "Price diverged {:.2}% from {}",
+            spread.abs(),
+            self.other_venue
+        );
+        let divergence = AlertDivergence {
+            venue: self.venue,
+            other_venue: self.other_venue,
+            other_price,
+        };
+        Some(
+            Alert::new(now, price, position, cause)
+                .with_divergence(divergence)
+                .with_sinks(&defaults.sinks),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::DivergenceWatch;
+    use crate::{
+        alert::Position, bot::PriceLevel, strategy::AlertDefaults,
+        venue::VenueKind,
+    };
+
+    #[test]
+    fn prices_moving_apart_alert_once() {
+        let defaults = AlertDefaults {
+            cooldown: Duration::from_secs(60),
+            ..AlertDefaults::default()
+        };
+        let mut watch =
+            DivergenceWatch::new(VenueKind::Binance, VenueKind::Kraken, 0.5);
+        let start = Instant::now();
+        let mut check = |price: f64, other: f64, secs: u64| {
+            watch.check(
+                PriceLevel(price),
+                PriceLevel(other),
+                start + Duration::from_secs(secs),
+                &defaults,
+            )
+        };
+
+        assert!(check(1000.0, 1004.0, 0).is_none());
+        assert!(check(1000.0, f64::NAN, 0).is_none());
+        let alert = check(1000.0, 1006.0, 1).unwrap();
+        assert_eq!(alert.cause(), "Price diverged 0.60% from kraken");
+        assert_eq!(alert.position(), Position::Buy);
+        assert_eq!(alert.price().0, 1000.0);
+        // Still apart, no new alert until the prices come together.
+        assert!(check(1000.0, 1010.0, 2).is_none());
+        assert!(check(1000.0, 1001.0, 3).is_none());
+        // Apart again but cooling down.
+        assert!(check(1000.0, 994.0, 4).is_none());
+        assert!(check(1000.0, 1000.0, 5).is_none());
+
+        let alert = check(1000.0, 994.0, 90).unwrap();
+        assert_eq!(alert.position(), Position::Sell);
+    }
+}
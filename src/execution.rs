@@ -0,0 +1,544 @@
+use std::{fmt, str::FromStr};
+
+use binance::{account::Account, api::Binance, config::Config};
+
+use crate::{
+    alert::{Alert, Position},
+    bot::PriceLevel,
+    price::Price,
+    save::OrderLog,
+    symbol::SymbolInfo,
+};
+
+/// Settings of the order execution.
+#[derive(Debug, Clone, Copy)]
+pub struct ExecutionConfig {
+    pub kind: OrderKind,
+    /// Value of each order in the quote currency.
+    pub size: f64,
+    /// Largest value of the net position in the quote currency.
+    pub max_position: f64,
+    /// Loss in the quote currency since the start of the day
+    /// after which no more orders are placed.
+    pub max_daily_loss: f64,
+}
+
+impl Default for ExecutionConfig {
+    fn default() -> Self {
+        Self {
+            kind: OrderKind::Limit,
+            size: 100.0,
+            max_position: 1000.0,
+            max_daily_loss: 100.0,
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub enum OrderKind {
+    Market,
+    /// Limit order at the price of the alert.
+    Limit,
+}
+
+impl FromStr for OrderKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "market" => Ok(OrderKind::Market),
+            "limit" => Ok(OrderKind::Limit),
+            _ => Err(format!("Invalid order type: {s}")),
+        }
+    }
+}
+
+/// Order waiting for the user's confirmation.
+#[derive(Debug, Clone, Copy)]
+pub struct OrderRequest {
+    pub side: Position,
+    pub kind: OrderKind,
+    /// Amount of the base currency.
+    pub quantity: f64,
+    /// Limit price or the expected price of the market order.
+    pub price: PriceLevel,
+}
+
+impl fmt::Display for OrderRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:?} {} {:?} @ {}",
+            self.side, self.quantity, self.kind, self.price.0
+        )
+    }
+}
+
+/// Order sent to the exchange or only logged in the dry-run mode.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct OrderRecord {
+    /// Unix time in seconds.
+    pub time: u64,
+    pub symbol: String,
+    pub side: Position,
+    pub kind: OrderKind,
+    pub quantity: f64,
+    pub price: f64,
+    pub status: OrderStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum OrderStatus {
+    /// Order which would have been placed outside of the dry-run mode.
+    DryRun,
+    Placed {
+        id: u64,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+/// Exchange the orders are placed on.
+pub trait Exchange {
+    /// Places the order and returns its id on the exchange.
+    fn place_order(
+        &self,
+        symbol: &str,
+        order: &OrderRequest,
+    ) -> Result<u64, String>;
+}
+
+/// Places the orders on Binance or on a server with the same API.
+pub struct BinanceExchange {
+    account: Account,
+}
+
+impl BinanceExchange {
+    /// Connects to the `endpoint` or to Binance if it is `None`.
+    pub fn new(
+        api_key: String,
+        secret_key: String,
+        endpoint: Option<String>,
+    ) -> Self {
+        let mut config = Config::default();
+        if let Some(endpoint) = endpoint {
+            config.rest_api_endpoint = endpoint;
+        }
+        Self {
+            account: Account::new_with_config(
+                Some(api_key),
+                Some(secret_key),
+                &config,
+            ),
+        }
+    }
+}
+
+impl Exchange for BinanceExchange {
+    fn place_order(
+        &self,
+        symbol: &str,
+        order: &OrderRequest,
+    ) -> Result<u64, String> {
+        let (quantity, price) = (order.quantity, order.price.0);
+        let transaction = match (order.side, order.kind) {
+            (Position::Buy, OrderKind::Market) => {
+                self.account.market_buy(symbol, quantity)
+            }
+            (Position::Sell, OrderKind::Market) => {
+                self.account.market_sell(symbol, quantity)
+            }
+            (Position::Buy, OrderKind::Limit) => {
+                self.account.limit_buy(symbol, quantity, price)
+            }
+            (Position::Sell, OrderKind::Limit) => {
+                self.account.limit_sell(symbol, quantity, price)
+            }
+        };
+        transaction.map(|t| t.order_id).map_err(|e| e.to_string())
+    }
+}
+
+/// Turns the alerts into orders once the user confirms them. Without an
+/// exchange the orders are only logged, which is the dry-run mode.
+///
+/// The position and the daily loss are tracked from the confirmed orders
+/// as if they were filled right away, including the dry-run ones, so the
+/// limits behave the same in both modes.
+pub struct Executor {
+    config: ExecutionConfig,
+    exchange: Option<Box<dyn Exchange>>,
+    /// Trading rules the orders are rounded to, `None` if they are not
+    /// known.
+    symbol_info: Option<SymbolInfo>,
+    log: Option<OrderLog>,
+    pending: Option<OrderRequest>,
+    /// Whether the kill switch stopped all orders.
+    killed: bool,
+
+    /// Net position in the base currency, negative when short.
+    position: f64,
+    /// Quote currency spent and received by the orders.
+    cash: f64,
+    /// Day since the Unix epoch the daily loss is counted for.
+    day: u64,
+    /// Value of the position and the cash at the start of the day.
+    day_start_value: f64,
+    price: PriceLevel,
+    last_order: Option<OrderRecord>,
+}
+
+impl Executor {
+    /// Quantity step of the orders when the lot size is not known.
+    const DEFAULT_LOT_SIZE: Price = Price::new(1, 5);
+
+    /// Creates the executor which places the orders on the `exchange`
+    /// or only logs them if it is `None`.
+    pub fn new(
+        config: ExecutionConfig,
+        exchange: Option<Box<dyn Exchange>>,
+    ) -> Self {
+        Self {
+            config,
+            exchange,
+            symbol_info: None,
+            log: None,
+            pending: None,
+            killed: false,
+            position: 0.0,
+            cash: 0.0,
+            day: 0,
+            day_start_value: f64::NAN,
+            price: PriceLevel::NAN,
+            last_order: None,
+        }
+    }
+
+    /// Writes every confirmed order to the `log`.
+    pub fn log_orders(&mut self, log: OrderLog) {
+        self.log = Some(log);
+    }
+
+    /// Sets the trading rules of the traded symbol. The quantity of the
+    /// orders is rounded down to the lot size and the price to the tick
+    /// size so the exchange accepts them.
+    pub fn set_symbol_info(&mut self, info: Option<SymbolInfo>) {
+        self.symbol_info = info;
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.exchange.is_none()
+    }
+
+    /// Prepares the order suggested by the `alert` for the confirmation.
+    /// Replaces the order waiting for the confirmation if there is one.
+    /// Returns whether the order was prepared, it isn't if its quantity
+    /// is below the minimum quantity of the symbol.
+    pub fn propose(&mut self, alert: &Alert) -> bool {
+        let price = alert.price();
+        if self.killed || price.0.is_nan() || price.0 <= 0.0 {
+            return false;
+        }
+        let info = self.symbol_info.as_ref();
+        let lot_size = info
+            .and_then(|info| info.lot_size)
+            .unwrap_or(Self::DEFAULT_LOT_SIZE);
+        let min_quantity =
+            info.and_then(|info| info.min_quantity).unwrap_or(lot_size);
+        let quantity = Price::from_f64(self.config.size / price.0)
+            .map(|quantity| quantity.floor_to(lot_size))
+            .filter(|quantity| {
+                quantity.is_positive() && *quantity >= min_quantity
+            });
+        let Some(quantity) = quantity else {
+            return false;
+        };
+        let price = match (
+            info.and_then(|info| info.tick_size),
+            Price::from_f64(price.0),
+        ) {
+            (Some(tick), Some(exact)) => exact.round_to(tick).level(),
+            _ => price,
+        };
+        self.pending = Some(OrderRequest {
+            side: alert.position(),
+            kind: self.config.kind,
+            quantity: quantity.to_f64(),
+            price,
+        });
+        true
+    }
+
+    /// Order waiting for the user's confirmation.
+    pub fn pending(&self) -> Option<&OrderRequest> {
+        self.pending.as_ref()
+    }
+
+    /// Places the order waiting for the confirmation unless the kill
+    /// switch is engaged or the order breaks the limits.
+    pub fn confirm(
+        &mut self,
+        symbol: &str,
+        unix_time: u64,
+    ) -> Result<&OrderRecord, String> {
+        let mut order =
+            self.pending.take().ok_or("there is no order to confirm")?;
+        if self.killed {
+            return Err(String::from("the kill switch is engaged"));
+        }
+        if order.kind == OrderKind::Market && !self.price.0.is_nan() {
+            order.price = self.price;
+        }
+        let change = match order.side {
+            Position::Buy => order.quantity,
+            Position::Sell => -order.quantity,
+        };
+        if (self.position + change).abs() * order.price.0
+            > self.config.max_position
+        {
+            return Err(String::from("the order exceeds the max position"));
+        }
+        if self.daily_loss() >= self.config.max_daily_loss {
+            return Err(String::from("the daily loss limit is reached"));
+        }
+
+        let status = match &self.exchange {
+            None => OrderStatus::DryRun,
+            Some(exchange) => match exchange.place_order(symbol, &order) {
+                Ok(id) => OrderStatus::Placed { id },
+                Err(error) => OrderStatus::Failed { error },
+            },
+        };
+        let record = OrderRecord {
+            time: unix_time,
+            symbol: symbol.to_owned(),
+            side: order.side,
+            kind: order.kind,
+            quantity: order.quantity,
+            price: order.price.0,
+            status,
+        };
+        // Stop logging if the file can't be written to anymore
+        // instead of stopping the bot.
+        if let Some(log) = &mut self.log {
+            if log.append(&record).is_err() {
+                self.log = None;
+            }
+        }
+        if let OrderStatus::Failed { error } = &record.status {
+            let error = format!("the order failed: {error}");
+            self.last_order = Some(record);
+            return Err(error);
+        }
+        self.position += change;
+        self.cash -= change * order.price.0;
+        Ok(self.last_order.insert(record))
+    }
+
+    /// Drops the order waiting for the confirmation.
+    pub fn reject(&mut self) {
+        self.pending = None;
+    }
+
+    /// Engaging the kill switch drops the order waiting for the
+    /// confirmation and stops all orders until it is released.
+    pub fn set_killed(&mut self, killed: bool) {
+        self.killed = killed;
+        if killed {
+            self.pending = None;
+        }
+    }
+
+    pub fn is_killed(&self) -> bool {
+        self.killed
+    }
+
+    /// Values the position at the `price` and starts counting
+    /// the daily loss again when a new day begins.
+    pub fn track(&mut self, price: PriceLevel, unix_time: u64) {
+        if price.0.is_nan() {
+            return;
+        }
+        self.price = price;
+        let day = unix_time / (24 * 60 * 60);
+        if day != self.day || self.day_start_value.is_nan() {
+            self.day = day;
+            self.day_start_value = self.value();
+        }
+    }
+
+    fn value(&self) -> f64 {
+        self.cash + self.position * self.price.0
+    }
+
+    /// Loss since the start of the day in the quote currency,
+    /// negative if there is a profit.
+    pub fn daily_loss(&self) -> f64 {
+        let loss = self.day_start_value - self.value();
+        if loss.is_nan() {
+            0.0
+        } else {
+            loss
+        }
+    }
+
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    pub fn last_order(&self) -> Option<&OrderRecord> {
+        self.last_order.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread,
+        time::Instant,
+    };
+
+    use super::{
+        BinanceExchange, Exchange, ExecutionConfig, Executor, OrderKind,
+        OrderRequest, OrderStatus,
+    };
+    use crate::{
+        alert::{Alert, Position},
+        bot::PriceLevel,
+        price::Price,
+        symbol::{Symbol, SymbolInfo},
+    };
+
+    fn alert(position: Position, price: f64) -> Alert {
+        Alert::new(Instant::now(), PriceLevel(price), position, String::new())
+    }
+
+    #[test]
+    fn limits_and_kill_switch_stop_orders() {
+        let config = ExecutionConfig {
+            kind: OrderKind::Market,
+            size: 500.0,
+            max_position: 1200.0,
+            max_daily_loss: 50.0,
+        };
+        let mut executor = Executor::new(config, None);
+        executor.track(PriceLevel(1000.0), 0);
+
+        assert!(executor.confirm("ETHUSDT", 0).is_err());
+        assert!(executor.propose(&alert(Position::Buy, 1000.0)));
+        let order = executor.confirm("ETHUSDT", 0).unwrap();
+        assert_eq!(order.status, OrderStatus::DryRun);
+        assert_eq!(order.quantity, 0.5);
+
+        executor.propose(&alert(Position::Buy, 1000.0));
+        executor.propose(&alert(Position::Buy, 1000.0));
+        executor.confirm("ETHUSDT", 0).unwrap();
+        // Third buy would make the position worth 1500.
+        executor.propose(&alert(Position::Buy, 1000.0));
+        assert!(executor.confirm("ETHUSDT", 0).is_err());
+        assert_eq!(executor.position(), 1.0);
+
+        // Losing 60 on the position of 1 ETH.
+        executor.track(PriceLevel(940.0), 60);
+        assert_eq!(executor.daily_loss(), 60.0);
+        executor.propose(&alert(Position::Sell, 940.0));
+        assert!(executor.confirm("ETHUSDT", 60).is_err());
+
+        // The loss is counted again the next day.
+        executor.track(PriceLevel(940.0), 24 * 60 * 60);
+        executor.set_killed(true);
+        assert!(!executor.propose(&alert(Position::Sell, 940.0)));
+        executor.set_killed(false);
+        executor.propose(&alert(Position::Sell, 940.0));
+        assert!(executor.confirm("ETHUSDT", 24 * 60 * 60).is_ok());
+    }
+
+    #[test]
+    fn orders_follow_symbol_rules() {
+        let mut executor = Executor::new(ExecutionConfig::default(), None);
+        executor.set_symbol_info(Some(SymbolInfo {
+            symbol: Symbol::new("SOLUSDT"),
+            base: String::from("SOL"),
+            quote: String::from("USDT"),
+            status: String::from("TRADING"),
+            tick_size: Some(Price::new(5, 2)),
+            lot_size: Some(Price::new(1, 2)),
+            min_quantity: Some(Price::new(50, 2)),
+        }));
+
+        // 100 / 151.13 is 0.66168... SOL.
+        assert!(executor.propose(&alert(Position::Buy, 151.13)));
+        let order = executor.pending().unwrap();
+        assert_eq!(order.quantity, 0.66);
+        assert_eq!(order.price.0, 151.15);
+
+        // 100 / 250 is below the minimum of 0.5 SOL.
+        executor.reject();
+        assert!(!executor.propose(&alert(Position::Buy, 250.0)));
+        assert!(executor.pending().is_none());
+    }
+
+    /// Answers every request with the `response` and sends
+    /// the request lines back.
+    fn mock_exchange(
+        response: &'static str,
+    ) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            reader.read_line(&mut request).unwrap();
+            // Skip the headers, the order is in the query.
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                response.len()
+            )
+            .unwrap();
+            request
+        });
+        (url, server)
+    }
+
+    #[test]
+    fn orders_are_placed_on_the_exchange() {
+        let (url, server) = mock_exchange(
+            r#"{"symbol": "ETHUSDT", "orderId": 42, "orderListId": -1,
+                "clientOrderId": "a", "transactTime": 0, "price": "1000.0",
+                "origQty": "0.1", "executedQty": "0.0",
+                "cummulativeQuoteQty": "0.0", "status": "NEW",
+                "timeInForce": "GTC", "type": "LIMIT", "side": "BUY"}"#,
+        );
+        let exchange = BinanceExchange::new(
+            String::from("key"),
+            String::from("secret"),
+            Some(url),
+        );
+        let order = OrderRequest {
+            side: Position::Buy,
+            kind: OrderKind::Limit,
+            quantity: 0.1,
+            price: PriceLevel(1000.0),
+        };
+
+        assert_eq!(exchange.place_order("ETHUSDT", &order), Ok(42));
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /api/v3/order?"));
+        for param in ["symbol=ETHUSDT", "side=BUY", "type=LIMIT", "price=1000"]
+        {
+            assert!(request.contains(param), "missing {param}");
+        }
+    }
+}
@@ -0,0 +1,360 @@
+use std::{collections::BTreeMap, fmt::Write, fs, io, path::Path};
+
+use crate::{
+    alert::{Outcome, Position},
+    save::{AlertRecord, ZoneData},
+};
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+/// How many moves are listed in the daily summary.
+const BIGGEST_MOVES: usize = 5;
+
+/// Writes the zones as CSV or pretty JSON, depending on the file extension.
+/// The CSV can be imported back with [`crate::import::parse_csv`].
+pub fn export_zones<P: AsRef<Path>>(
+    zones: &[ZoneData],
+    path: P,
+) -> io::Result<()> {
+    let content = match Format::of(path.as_ref())? {
+        Format::Csv => zones_csv(zones),
+        Format::Json => serde_json::to_string_pretty(zones).unwrap(),
+    };
+    fs::write(path, content)
+}
+
+/// Writes the alert history as CSV or pretty JSON,
+/// depending on the file extension.
+pub fn export_alerts<P: AsRef<Path>>(
+    alerts: &[AlertRecord],
+    path: P,
+) -> io::Result<()> {
+    let content = match Format::of(path.as_ref())? {
+        Format::Csv => alerts_csv(alerts),
+        Format::Json => serde_json::to_string_pretty(alerts).unwrap(),
+    };
+    fs::write(path, content)
+}
+
+/// Writes the Markdown summary of the alerts from the `day`,
+/// counted in days since the Unix epoch.
+pub fn export_summary<P: AsRef<Path>>(
+    alerts: &[AlertRecord],
+    day: u64,
+    path: P,
+) -> io::Result<()> {
+    fs::write(path, daily_summary(alerts, day))
+}
+
+enum Format {
+    Csv,
+    Json,
+}
+
+impl Format {
+    fn of(path: &Path) -> io::Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => Ok(Format::Csv),
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Ok(Format::Json),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Only .csv and .json exports are supported",
+            )),
+        }
+    }
+}
+
+fn zones_csv(zones: &[ZoneData]) -> String {
+    let mut csv = String::from("symbol,low,high,priority,note,tags\n");
+    for zone in zones {
+        let _ = writeln!(
+            csv,
+            "{},{},{},{:?},{},{}",
+            csv_field(zone.symbol.as_deref().unwrap_or("")),
+            zone.low.0,
+            zone.high.0,
+            zone.priority,
+            csv_field(zone.note.as_deref().unwrap_or("")),
+            csv_field(&zone.tags.join(";"))
+        );
+    }
+    csv
+}
+
+fn alerts_csv(alerts: &[AlertRecord]) -> String {
+    let mut csv = String::from(
+        "time,symbol,price,position,zone_priority,zone_low,zone_high,\
+         outcome,best_move,worst_move,cause\n",
+    );
+    for alert in alerts {
+        let (priority, low, high) = match &alert.zone {
+            Some(z) => (
+                format!("{:?}", z.priority),
+                z.low.0.to_string(),
+                z.high.0.to_string(),
+            ),
+            None => Default::default(),
+        };
+        let outcome = alert.outcome.map(|o| format!("{o:?}"));
+        let _ = writeln!(
+            csv,
+            "{},{},{},{:?},{},{},{},{},{:.2},{:.2},{}",
+            format_time(alert.time),
+            csv_field(&alert.symbol),
+            alert.price.0,
+            alert.position,
+            priority,
+            low,
+            high,
+            outcome.unwrap_or_default(),
+            alert.best_move,
+            alert.worst_move,
+            csv_field(&alert.cause)
+        );
+    }
+    csv
+}
+
+/// Markdown summary of the day with the alert count and the hit rate
+/// of every zone and the biggest moves after the alerts.
+pub fn daily_summary(alerts: &[AlertRecord], day: u64) -> String {
+    let alerts: Vec<&AlertRecord> = alerts
+        .iter()
+        .filter(|a| a.time / SECS_PER_DAY == day)
+        .collect();
+
+    let mut md = format!("# Zone bot summary for {}\n\n", format_date(day));
+    if alerts.is_empty() {
+        md.push_str("No alerts.\n");
+        return md;
+    }
+
+    let mut per_zone: BTreeMap<String, ZoneStats> = BTreeMap::new();
+    for alert in &alerts {
+        let zone = match &alert.zone {
+            Some(z) => format!(
+                "{} {:?} {} - {}",
+                alert.symbol, z.priority, z.low.0, z.high.0
+            ),
+            None => format!("{} (no zone)", alert.symbol),
+        };
+        per_zone.entry(zone).or_default().add(alert.outcome);
+    }
+
+    let total = per_zone
+        .values()
+        .fold(ZoneStats::default(), |total, s| total.merge(s));
+    let _ = writeln!(
+        md,
+        "{} alert(s), {} hit(s), {} miss(es), hit rate {}.\n",
+        total.alerts,
+        total.hits,
+        total.misses,
+        total.hit_rate()
+    );
+
+    md.push_str("## Alerts per zone\n\n");
+    md.push_str("| Zone | Alerts | Hits | Misses | Hit rate |\n");
+    md.push_str("|---|---:|---:|---:|---:|\n");
+    for (zone, stats) in &per_zone {
+        let _ = writeln!(
+            md,
+            "| {} | {} | {} | {} | {} |",
+            zone,
+            stats.alerts,
+            stats.hits,
+            stats.misses,
+            stats.hit_rate()
+        );
+    }
+
+    let mut moves = alerts.clone();
+    moves.sort_by(|a, b| b.best_move.total_cmp(&a.best_move));
+    md.push_str("\n## Biggest moves\n\n");
+    md.push_str("| Time | Symbol | Position | Price | Move |\n");
+    md.push_str("|---|---|---|---:|---:|\n");
+    for alert in moves.iter().take(BIGGEST_MOVES) {
+        let _ = writeln!(
+            md,
+            "| {} | {} | {} | {} | {:+.2}% |",
+            format_time(alert.time),
+            alert.symbol,
+            match alert.position {
+                Position::Buy => "Buy",
+                Position::Sell => "Sell",
+            },
+            alert.price.0,
+            alert.best_move
+        );
+    }
+    md
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct ZoneStats {
+    alerts: usize,
+    hits: usize,
+    misses: usize,
+}
+
+impl ZoneStats {
+    fn add(&mut self, outcome: Option<Outcome>) {
+        self.alerts += 1;
+        match outcome {
+            Some(Outcome::Hit) => self.hits += 1,
+            Some(Outcome::Miss) => self.misses += 1,
+            Some(Outcome::Expired) | None => (),
+        }
+    }
+
+    fn merge(self, other: &ZoneStats) -> Self {
+        Self {
+            alerts: self.alerts + other.alerts,
+            hits: self.hits + other.hits,
+            misses: self.misses + other.misses,
+        }
+    }
+
+    /// Share of the hits among the decided alerts.
+    fn hit_rate(&self) -> String {
+        match self.hits + self.misses {
+            0 => String::from("-"),
+            decided => {
+                format!("{:.0}%", self.hits as f64 / decided as f64 * 100.0)
+            }
+        }
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Formats days since the Unix epoch as `YYYY-MM-DD`.
+pub fn format_date(day: u64) -> String {
+    let (y, m, d) = civil_from_days(day as i64);
+    format!("{y:04}-{m:02}-{d:02}")
+}
+
+/// Formats the Unix time as `YYYY-MM-DD HH:MM:SS` in UTC.
+pub fn format_time(time: u64) -> String {
+    let secs = time % SECS_PER_DAY;
+    format!(
+        "{} {:02}:{:02}:{:02}",
+        format_date(time / SECS_PER_DAY),
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+/// Parses `YYYY-MM-DD` into days since the Unix epoch.
+pub fn parse_date(date: &str) -> Option<u64> {
+    let mut parts = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (Some(Some(y)), Some(Some(m)), Some(Some(d))) =
+        (parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return None;
+    }
+    let days = days_from_civil(y, m, d);
+    (days >= 0 && civil_from_days(days) == (y, m, d)).then_some(days as u64)
+}
+
+// Conversions between the Gregorian calendar and days since the Unix epoch
+// from http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    (y, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{daily_summary, format_time, parse_date, zones_csv};
+    use crate::{
+        alert::{Outcome, Position},
+        import::parse_csv,
+        price::Price,
+        save::{AlertRecord, AlertZoneData, PriceLevelData, PriorityData},
+    };
+
+    fn alert(time: u64, outcome: Outcome, best_move: f64) -> AlertRecord {
+        AlertRecord {
+            time,
+            symbol: String::from("ETHUSDT"),
+            price: PriceLevelData(Price::new(1250, 0)),
+            position: Position::Buy,
+            cause: String::from("Price entered High zone 1200 - 1250"),
+            zone: Some(AlertZoneData {
+                priority: PriorityData::High,
+                high: PriceLevelData(Price::new(1250, 0)),
+                low: PriceLevelData(Price::new(1200, 0)),
+            }),
+            outcome: Some(outcome),
+            best_move,
+            worst_move: -0.5,
+        }
+    }
+
+    #[test]
+    fn dates_convert_both_ways() {
+        let day = parse_date("2022-11-15").unwrap();
+        assert_eq!(day, 19311);
+        assert_eq!(format_time(day * 86400 + 3723), "2022-11-15 01:02:03");
+        assert_eq!(parse_date("2022-02-30"), None);
+    }
+
+    #[test]
+    fn exported_zones_import_back() {
+        let csv = "symbol,low,high,priority,note\n\
+                   ETHUSDT,1200,1250,High,\"weekly open, \"\"FOMC\"\"\"\n\
+                   BTCUSDT,16000,16500,Low,,range;weekly\n";
+        let zones = parse_csv(csv).unwrap();
+
+        assert_eq!(parse_csv(&zones_csv(&zones)).unwrap(), zones);
+    }
+
+    #[test]
+    fn summary_counts_alerts_per_zone() {
+        let day = parse_date("2022-11-15").unwrap();
+        let start = day * 86400;
+        let alerts = [
+            alert(start + 60, Outcome::Hit, 2.5),
+            alert(start + 120, Outcome::Miss, 0.2),
+            alert(start + 180, Outcome::Hit, 1.4),
+            // The next day is not a part of the summary.
+            alert(start + 86400, Outcome::Miss, 0.0),
+        ];
+        let summary = daily_summary(&alerts, day);
+
+        assert!(summary.contains("# Zone bot summary for 2022-11-15"));
+        assert!(
+            summary.contains("| ETHUSDT High 1200 - 1250 | 3 | 2 | 1 | 67% |")
+        );
+        assert!(summary.contains(
+            "| 2022-11-15 00:01:00 | ETHUSDT | Buy | 1250 | +2.50% |"
+        ));
+    }
+}
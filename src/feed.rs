@@ -0,0 +1,611 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{channel, Receiver, Sender},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use futures_util::{future::BoxFuture, stream::BoxStream, StreamExt};
+use tokio::{
+    runtime,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    task::{self, JoinHandle},
+    time::{self, Instant},
+};
+
+use crate::{
+    clock::Clock,
+    depth::{OrderBook, DEPTH_INTERVAL, DEPTH_LIMIT},
+    price::Price,
+    symbol::Symbol,
+    venue::{Venue, VenueKind},
+};
+
+/// Latest price of a symbol, parsed exactly from the venue's response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ticker {
+    pub symbol: Symbol,
+    pub price: Price,
+}
+
+/// Update of the market received by the bot. All the subscriptions of
+/// the feed send their updates to the same channel, so do the replays.
+#[derive(Debug)]
+pub enum MarketEvent {
+    /// Price of a symbol on the venue or the failed read of the prices.
+    Price(VenueKind, Result<Ticker, String>),
+    /// Order book of the symbol or its failed read.
+    Depth(Symbol, Result<OrderBook, String>),
+}
+
+/// Where the feed reads the prices from.
+pub trait PriceSource: Send + Sync + 'static {
+    /// Reads the latest prices of all the `symbols` at once.
+    /// The `symbols` are sorted.
+    fn prices(&self, symbols: &[Symbol]) -> Result<Vec<Ticker>, String>;
+}
+
+/// Shared sources, e.g. the venue the bot also reads the candles from.
+impl<S: PriceSource + ?Sized> PriceSource for Arc<S> {
+    fn prices(&self, symbols: &[Symbol]) -> Result<Vec<Ticker>, String> {
+        (**self).prices(symbols)
+    }
+}
+
+/// Price updates pushed by the venue.
+pub type TickerStream = BoxStream<'static, Result<Ticker, String>>;
+
+/// Where the feed streams the prices from. Unlike a [`PriceSource`] the
+/// venue sends the prices as they change instead of once per tick.
+pub trait PriceStream: Send + Sync + 'static {
+    /// Connects to the price updates of the `symbols`, which are sorted.
+    /// The stream ends or fails when the connection is lost.
+    fn connect(
+        &self,
+        symbols: &[Symbol],
+    ) -> BoxFuture<'static, Result<TickerStream, String>>;
+}
+
+/// How the prices of a venue are read.
+enum Source {
+    Poll(Arc<dyn PriceSource>),
+    Stream(Arc<dyn PriceStream>),
+}
+
+enum FeedCommand {
+    /// Reads the prices of the venue from the source instead of
+    /// the previous one, the subscribed symbols stay the same.
+    Source(VenueKind, Source),
+    Subscribe(VenueKind, Vec<Symbol>),
+    Unsubscribe(VenueKind, Symbol),
+    /// Reads the order book of the symbol instead of the previous one.
+    Depth(Arc<dyn Venue>, Symbol),
+    Stop,
+}
+
+#[derive(Debug)]
+enum SymbolCommand {
+    Subscribe(Vec<Symbol>),
+    Unsubscribe(Symbol),
+}
+
+/// Reads the market on an event loop running in a single background
+/// thread. Reading the market blocks for a while so doing it in the main
+/// thread would delay the user input.
+///
+/// Every venue is read by its own task on the event loop, either polled
+/// once per tick with one batch request for all its symbols or streamed.
+/// The order book is polled by another task. All of them send their
+/// updates to a single channel, a failed request sends its error once
+/// for all the symbols.
+#[derive(Debug)]
+pub struct MarketFeed {
+    commands: UnboundedSender<FeedCommand>,
+}
+
+impl MarketFeed {
+    /// Starts the event loop. The prices are polled once per `interval`
+    /// milliseconds of the `clock`, the interval is read before every wait
+    /// so it can change while the feed runs.
+    /// Returns the feed and the channel the updates are sent to.
+    pub fn start(
+        clock: Arc<dyn Clock>,
+        interval: Arc<AtomicU64>,
+    ) -> (Self, Receiver<MarketEvent>) {
+        let (commands, command_rx) = unbounded_channel();
+        let (tx, rx) = channel();
+        thread::spawn(move || {
+            let runtime = runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Creating the market feed runtime failed");
+            let sources =
+                runtime.block_on(run(command_rx, tx, clock, interval));
+            // A blocking HTTP client can't be dropped on the event loop so
+            // the sources are kept until the runtime is gone.
+            drop(runtime);
+            drop(sources);
+        });
+        (Self { commands }, rx)
+    }
+
+    /// Polls the prices of the `venue` from the `source`.
+    pub fn poll<S: PriceSource>(&self, venue: VenueKind, source: S) {
+        let source = Source::Poll(Arc::new(source));
+        let _ = self.commands.send(FeedCommand::Source(venue, source));
+    }
+
+    /// Streams the prices of the `venue` from the `source`.
+    pub fn stream(&self, venue: VenueKind, source: Arc<dyn PriceStream>) {
+        let source = Source::Stream(source);
+        let _ = self.commands.send(FeedCommand::Source(venue, source));
+    }
+
+    /// Adds the `symbols` to the prices read from the `venue`.
+    /// A new symbol is read right away.
+    pub fn subscribe(&self, venue: VenueKind, symbols: Vec<Symbol>) {
+        // Sending fails only after the feed stopped.
+        let _ = self.commands.send(FeedCommand::Subscribe(venue, symbols));
+    }
+
+    pub fn unsubscribe(&self, venue: VenueKind, symbol: Symbol) {
+        let _ = self.commands.send(FeedCommand::Unsubscribe(venue, symbol));
+    }
+
+    /// Reads the order book of the `symbol` from the `venue` once per
+    /// [`DEPTH_INTERVAL`] instead of the previously watched one.
+    pub fn watch_depth(&self, venue: Arc<dyn Venue>, symbol: Symbol) {
+        let _ = self.commands.send(FeedCommand::Depth(venue, symbol));
+    }
+
+    /// Stops the feed, no more updates are read.
+    pub fn stop(&self) {
+        let _ = self.commands.send(FeedCommand::Stop);
+    }
+}
+
+impl Drop for MarketFeed {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Subscriptions of a venue read by its task.
+struct VenueTask {
+    symbols: BTreeSet<Symbol>,
+    commands: UnboundedSender<SymbolCommand>,
+    task: JoinHandle<()>,
+}
+
+/// Event loop of the feed. Runs until the feed is stopped or dropped,
+/// then stops all the tasks and returns all the sources it has read from.
+async fn run(
+    mut commands: UnboundedReceiver<FeedCommand>,
+    tx: Sender<MarketEvent>,
+    clock: Arc<dyn Clock>,
+    interval: Arc<AtomicU64>,
+) -> Vec<Source> {
+    let mut venues: HashMap<VenueKind, VenueTask> = HashMap::new();
+    let mut depth: Option<JoinHandle<()>> = None;
+    let mut sources = Vec::new();
+
+    while let Some(command) = commands.recv().await {
+        match command {
+            FeedCommand::Source(venue, source) => {
+                let symbols = match venues.remove(&venue) {
+                    Some(old) => {
+                        old.task.abort();
+                        old.symbols
+                    }
+                    None => BTreeSet::new(),
+                };
+                let (symbol_tx, symbol_rx) = unbounded_channel();
+                let tx = tx.clone();
+                let task = match &source {
+                    Source::Poll(source) => tokio::spawn(poll(
+                        venue,
+                        source.clone(),
+                        symbol_rx,
+                        tx,
+                        clock.clone(),
+                        interval.clone(),
+                    )),
+                    Source::Stream(source) => tokio::spawn(stream(
+                        venue,
+                        source.clone(),
+                        symbol_rx,
+                        tx,
+                        clock.clone(),
+                    )),
+                };
+                if !symbols.is_empty() {
+                    let batch = symbols.iter().cloned().collect();
+                    let _ = symbol_tx.send(SymbolCommand::Subscribe(batch));
+                }
+                let commands = symbol_tx;
+                venues.insert(
+                    venue,
+                    VenueTask {
+                        symbols,
+                        commands,
+                        task,
+                    },
+                );
+                sources.push(source);
+            }
+            FeedCommand::Subscribe(venue, new) => {
+                if let Some(venue) = venues.get_mut(&venue) {
+                    venue.symbols.extend(new.iter().cloned());
+                    let _ = venue.commands.send(SymbolCommand::Subscribe(new));
+                }
+            }
+            FeedCommand::Unsubscribe(venue, symbol) => {
+                if let Some(venue) = venues.get_mut(&venue) {
+                    venue.symbols.remove(&symbol);
+                    let command = SymbolCommand::Unsubscribe(symbol);
+                    let _ = venue.commands.send(command);
+                }
+            }
+            FeedCommand::Depth(venue, symbol) => {
+                if let Some(task) = depth.take() {
+                    task.abort();
+                }
+                depth = Some(tokio::spawn(read_depth(
+                    venue.clone(),
+                    symbol,
+                    tx.clone(),
+                    clock.clone(),
+                )));
+                // Every venue is a price source too.
+                sources.push(Source::Poll(venue));
+            }
+            FeedCommand::Stop => break,
+        }
+    }
+    for venue in venues.values() {
+        venue.task.abort();
+    }
+    if let Some(task) = depth {
+        task.abort();
+    }
+    sources
+}
+
+/// Reads the prices of all the subscribed symbols of the `venue` with one
+/// batch request per tick. Runs until the feed or the receiver is gone.
+async fn poll(
+    venue: VenueKind,
+    source: Arc<dyn PriceSource>,
+    mut commands: UnboundedReceiver<SymbolCommand>,
+    tx: Sender<MarketEvent>,
+    clock: Arc<dyn Clock>,
+    interval: Arc<AtomicU64>,
+) {
+    let mut symbols = BTreeSet::new();
+    let mut next_read = Instant::now();
+
+    loop {
+        tokio::select! {
+            // Handle all the waiting commands before reading the prices.
+            biased;
+
+            command = commands.recv() => match command {
+                Some(command) => {
+                    if apply(command, &mut symbols) {
+                        next_read = Instant::now();
+                    }
+                }
+                None => return,
+            },
+            _ = time::sleep_until(next_read), if !symbols.is_empty() => {
+                let millis = interval.load(Ordering::Relaxed);
+                next_read = Instant::now()
+                    + clock.real_duration(Duration::from_millis(millis));
+
+                let batch: Vec<Symbol> = symbols.iter().cloned().collect();
+                let source = source.clone();
+                // The REST clients block so the request runs
+                // on the blocking pool instead of the event loop.
+                let prices =
+                    task::spawn_blocking(move || source.prices(&batch)).await;
+                // Symbols unsubscribed during the request are not sent.
+                while let Ok(command) = commands.try_recv() {
+                    if apply(command, &mut symbols) {
+                        next_read = Instant::now();
+                    }
+                }
+                let sent = match prices {
+                    Ok(Ok(prices)) => prices
+                        .into_iter()
+                        .filter(|p| symbols.contains(&p.symbol))
+                        .all(|price| {
+                            tx.send(MarketEvent::Price(venue, Ok(price)))
+                                .is_ok()
+                        }),
+                    Ok(Err(e)) => {
+                        tx.send(MarketEvent::Price(venue, Err(e))).is_ok()
+                    }
+                    // The request panicked, try again on the next tick.
+                    Err(_) => true,
+                };
+                if !sent {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Waits after a lost stream before connecting again.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Streams the prices of all the subscribed symbols of the `venue` over
+/// one connection. New symbols and a lost connection connect again.
+/// Runs until the feed or the receiver is gone.
+async fn stream(
+    venue: VenueKind,
+    source: Arc<dyn PriceStream>,
+    mut commands: UnboundedReceiver<SymbolCommand>,
+    tx: Sender<MarketEvent>,
+    clock: Arc<dyn Clock>,
+) {
+    let mut symbols = BTreeSet::new();
+    let reconnect_delay = clock.real_duration(RECONNECT_DELAY);
+
+    loop {
+        while symbols.is_empty() {
+            match commands.recv().await {
+                Some(command) => apply(command, &mut symbols),
+                None => return,
+            };
+        }
+        let batch: Vec<Symbol> = symbols.iter().cloned().collect();
+        let mut updates = match source.connect(&batch).await {
+            Ok(updates) => updates,
+            Err(e) => {
+                if tx.send(MarketEvent::Price(venue, Err(e))).is_err() {
+                    return;
+                }
+                time::sleep(reconnect_delay).await;
+                continue;
+            }
+        };
+        loop {
+            tokio::select! {
+                biased;
+
+                command = commands.recv() => match command {
+                    Some(command) => {
+                        if apply(command, &mut symbols) {
+                            break;
+                        }
+                    }
+                    None => return,
+                },
+                update = updates.next() => match update {
+                    Some(Ok(price)) => {
+                        if symbols.contains(&price.symbol)
+                            && tx.send(MarketEvent::Price(venue, Ok(price)))
+                                .is_err()
+                        {
+                            return;
+                        }
+                    }
+                    lost => {
+                        let e = match lost {
+                            Some(Err(e)) => e,
+                            _ => format!("The {venue} price stream closed"),
+                        };
+                        if tx.send(MarketEvent::Price(venue, Err(e))).is_err() {
+                            return;
+                        }
+                        time::sleep(reconnect_delay).await;
+                        break;
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Reads the order book of the `symbol` from the `venue` once per
+/// [`DEPTH_INTERVAL`] of the `clock`. Runs until it is replaced,
+/// the feed stops or the receiver is gone.
+async fn read_depth(
+    venue: Arc<dyn Venue>,
+    symbol: Symbol,
+    tx: Sender<MarketEvent>,
+    clock: Arc<dyn Clock>,
+) {
+    loop {
+        let (source, batch) = (venue.clone(), symbol.clone());
+        let book =
+            task::spawn_blocking(move || source.depth(&batch, DEPTH_LIMIT))
+                .await;
+        // A panicked request is tried again after the interval.
+        if let Ok(book) = book {
+            if tx.send(MarketEvent::Depth(symbol.clone(), book)).is_err() {
+                return;
+            }
+        }
+        time::sleep(clock.real_duration(DEPTH_INTERVAL)).await;
+    }
+}
+
+/// Applies the `command` to the subscribed `symbols`. Returns whether
+/// a new symbol was added.
+fn apply(command: SymbolCommand, symbols: &mut BTreeSet<Symbol>) -> bool {
+    match command {
+        SymbolCommand::Subscribe(new) => {
+            let before = symbols.len();
+            symbols.extend(new);
+            symbols.len() > before
+        }
+        SymbolCommand::Unsubscribe(symbol) => {
+            symbols.remove(&symbol);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::AtomicU64,
+            mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+            Arc, Mutex,
+        },
+        time::Duration,
+    };
+
+    use futures_util::{future::BoxFuture, stream, StreamExt};
+
+    use super::{
+        MarketEvent, MarketFeed, PriceSource, PriceStream, Ticker, TickerStream,
+    };
+    use crate::{
+        clock::SystemClock, price::Price, symbol::Symbol, venue::VenueKind,
+    };
+
+    /// Prices every symbol at its batch number and reports each batch.
+    struct FakeSource {
+        batches: Mutex<Sender<Vec<Symbol>>>,
+        count: Mutex<i64>,
+    }
+
+    impl PriceSource for FakeSource {
+        fn prices(&self, symbols: &[Symbol]) -> Result<Vec<Ticker>, String> {
+            let mut count = self.count.lock().unwrap();
+            *count += 1;
+            self.batches.lock().unwrap().send(symbols.to_vec()).unwrap();
+            Ok(symbols
+                .iter()
+                .map(|s| Ticker {
+                    symbol: s.clone(),
+                    price: Price::new(*count, 0),
+                })
+                .collect())
+        }
+    }
+
+    /// Streams the price of 0.5, which is never a batch number, for every
+    /// symbol once per connection and keeps the connection open.
+    struct FakeStream;
+
+    impl PriceStream for FakeStream {
+        fn connect(
+            &self,
+            symbols: &[Symbol],
+        ) -> BoxFuture<'static, Result<TickerStream, String>> {
+            let prices: Vec<Result<Ticker, String>> = symbols
+                .iter()
+                .map(|s| {
+                    Ok(Ticker {
+                        symbol: s.clone(),
+                        price: Price::new(5, 1),
+                    })
+                })
+                .collect();
+            let updates = stream::iter(prices).chain(stream::pending());
+            Box::pin(async move { Ok(updates.boxed()) })
+        }
+    }
+
+    fn feed() -> (MarketFeed, Receiver<MarketEvent>, Receiver<Vec<Symbol>>) {
+        let (batches, batch_rx) = channel();
+        let source = FakeSource {
+            batches: Mutex::new(batches),
+            count: Mutex::new(0),
+        };
+        let interval = Arc::new(AtomicU64::new(20));
+        let (feed, rx) = MarketFeed::start(Arc::new(SystemClock), interval);
+        feed.poll(VenueKind::Binance, source);
+        (feed, rx, batch_rx)
+    }
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Receives the next price and the venue it came from.
+    fn price(rx: &Receiver<MarketEvent>) -> (VenueKind, Symbol, Price) {
+        match rx.recv_timeout(TIMEOUT).unwrap() {
+            MarketEvent::Price(venue, Ok(p)) => (venue, p.symbol, p.price),
+            event => panic!("{event:?} is not a price"),
+        }
+    }
+
+    #[test]
+    fn symbols_are_read_in_one_batch() {
+        let (feed, rx, batches) = feed();
+        let symbols: Vec<Symbol> =
+            ["SOLUSDT", "BTCUSDT", "ETHUSDT"].map(Symbol::new).into();
+        feed.subscribe(VenueKind::Binance, symbols);
+
+        let batch = batches.recv_timeout(TIMEOUT).unwrap();
+        assert_eq!(batch, ["BTCUSDT", "ETHUSDT", "SOLUSDT"].map(Symbol::new));
+        let prices: Vec<(VenueKind, Symbol, Price)> =
+            (0..3).map(|_| price(&rx)).collect();
+        let one = Price::new(1, 0);
+        assert_eq!(
+            prices,
+            ["BTCUSDT", "ETHUSDT", "SOLUSDT"].map(|s| (
+                VenueKind::Binance,
+                Symbol::new(s),
+                one
+            ))
+        );
+
+        feed.unsubscribe(VenueKind::Binance, Symbol::new("BTCUSDT"));
+        let batch = (0..100)
+            .map(|_| batches.recv_timeout(TIMEOUT).unwrap())
+            .find(|batch| batch.len() == 2)
+            .unwrap();
+        assert_eq!(batch, ["ETHUSDT", "SOLUSDT"].map(Symbol::new));
+    }
+
+    #[test]
+    fn polled_and_streamed_prices_share_the_channel() {
+        let (feed, rx, batches) = feed();
+        feed.subscribe(VenueKind::Binance, vec![Symbol::new("ETHUSDT")]);
+        batches.recv_timeout(TIMEOUT).unwrap();
+        feed.stream(VenueKind::Kraken, Arc::new(FakeStream));
+        feed.subscribe(VenueKind::Kraken, vec![Symbol::new("ETHUSDT")]);
+
+        let streamed = (0..100)
+            .map(|_| price(&rx))
+            .find(|(venue, _, _)| *venue == VenueKind::Kraken)
+            .unwrap();
+        assert_eq!(
+            streamed,
+            (VenueKind::Kraken, Symbol::new("ETHUSDT"), Price::new(5, 1))
+        );
+
+        // Streaming the polled venue keeps its symbols.
+        feed.stream(VenueKind::Binance, Arc::new(FakeStream));
+        let streamed =
+            (0..100).map(|_| price(&rx)).find(|(venue, _, price)| {
+                *venue == VenueKind::Binance && *price == Price::new(5, 1)
+            });
+        assert!(streamed.is_some());
+    }
+
+    #[test]
+    fn dropped_feed_stops_reading() {
+        let (feed, rx, batches) = feed();
+        feed.subscribe(VenueKind::Binance, vec![Symbol::new("ETHUSDT")]);
+        batches.recv_timeout(TIMEOUT).unwrap();
+
+        drop(feed);
+        // The channel closes once the event loop ends.
+        let closed = loop {
+            if let Err(e) = rx.recv_timeout(TIMEOUT) {
+                break e;
+            }
+        };
+        assert_eq!(closed, RecvTimeoutError::Disconnected);
+    }
+}
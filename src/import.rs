@@ -0,0 +1,376 @@
+use std::{collections::BTreeMap, fmt, fs, io, path::Path};
+
+use crate::{
+    alert::AlertRules,
+    price::Price,
+    save::{LifecycleData, PriceLevelData, PriorityData, SaveData, ZoneData},
+    symbol::Symbol,
+};
+
+/// Reads the zones from a CSV or a TradingView drawing export,
+/// depending on the file extension, and merges them into the `save`.
+pub fn import_file<P: AsRef<Path>>(
+    path: P,
+    save: &mut SaveData,
+) -> io::Result<ImportReport> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)?;
+    let zones = match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("csv") => parse_csv(&content)?,
+        Some(ext) if ext.eq_ignore_ascii_case("json") => {
+            parse_tradingview(&content)?
+        }
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Only .csv and .json files can be imported",
+            ))
+        }
+    };
+    Ok(merge(save, zones))
+}
+
+/// Parses zones from CSV rows in the `symbol,low,high,priority,note,tags`
+/// format. The header row is optional, the note may be quoted and the tags
+/// are separated by semicolons. Both the note and the tags may be left out.
+pub fn parse_csv(content: &str) -> io::Result<Vec<ZoneData>> {
+    let mut zones = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = split_csv_line(line);
+        if i == 0 && fields[0].eq_ignore_ascii_case("symbol") {
+            continue;
+        }
+        let invalid =
+            |msg: &str| invalid_data(format!("Line {}: {msg}", i + 1));
+
+        if !(4..=6).contains(&fields.len()) {
+            return Err(invalid(
+                "expected symbol,low,high,priority[,note[,tags]]",
+            ));
+        }
+        let low = parse_price(&fields[1]).ok_or_else(|| invalid("bad low"))?;
+        let high =
+            parse_price(&fields[2]).ok_or_else(|| invalid("bad high"))?;
+        if low > high {
+            return Err(invalid("low is above high"));
+        }
+        let priority = parse_priority(&fields[3])
+            .ok_or_else(|| invalid("bad priority"))?;
+
+        zones.push(ZoneData {
+            symbol: non_empty(&fields[0]).map(|s| normalize_symbol(&s)),
+            priority,
+            high: PriceLevelData(high),
+            low: PriceLevelData(low),
+            note: fields.get(4).and_then(|n| non_empty(n)),
+            tags: fields
+                .get(5)
+                .map(|t| t.split(';').filter_map(non_empty).collect())
+                .unwrap_or_default(),
+            rules: AlertRules::default(),
+            lifecycle: LifecycleData::default(),
+            symbols: BTreeMap::new(),
+        });
+    }
+    Ok(zones)
+}
+
+/// Parses rectangles from a TradingView-style drawing export. The export is
+/// either a list of drawings or an object with a `drawings` or a `sources`
+/// list. Every drawing looks like:
+///
+/// ```json
+/// {
+///     "type": "LineToolRectangle",
+///     "symbol": "BINANCE:ETHUSDT",
+///     "points": [{ "time": 1668470400, "price": 1200.5 },
+///                { "time": 1668556800, "price": 1250.0 }],
+///     "state": { "text": "weekly open" }
+/// }
+/// ```
+///
+/// Drawings other than rectangles are skipped. TradingView has no notion of
+/// priority so it is read from `state.priority` and defaults to medium.
+pub fn parse_tradingview(content: &str) -> io::Result<Vec<ZoneData>> {
+    let export: tv::Export = serde_json::from_str(content)
+        .map_err(|e| invalid_data(format!("Invalid drawing export: {e}")))?;
+
+    let mut zones = Vec::new();
+    for (i, drawing) in export.drawings().into_iter().enumerate() {
+        if !drawing.kind.contains("Rectangle") {
+            continue;
+        }
+        let prices = drawing.points.iter().map(|p| p.price);
+        let (low, high) = (prices.clone().min(), prices.max());
+        let (Some(low), Some(high)) = (low, high) else {
+            return Err(rectangle_error(i));
+        };
+        if drawing.points.len() < 2 || !low.is_positive() {
+            return Err(rectangle_error(i));
+        }
+        let priority = match drawing.state.priority {
+            Some(p) => parse_priority(&p).ok_or_else(|| {
+                invalid_data(format!("Drawing {}: bad priority", i + 1))
+            })?,
+            None => PriorityData::Medium,
+        };
+
+        zones.push(ZoneData {
+            symbol: drawing.symbol.as_deref().map(normalize_symbol),
+            priority,
+            high: PriceLevelData(high),
+            low: PriceLevelData(low),
+            note: drawing.state.text.as_deref().and_then(non_empty),
+            tags: Vec::new(),
+            rules: AlertRules::default(),
+            lifecycle: LifecycleData::default(),
+            symbols: BTreeMap::new(),
+        });
+    }
+    Ok(zones)
+}
+
+/// Adds the `imported` zones to the `save`. Zones with the same bounds as an
+/// existing zone are duplicates and zones overlapping an existing zone are
+/// conflicts. Neither is added so the user can resolve them by hand. Zones
+/// without a symbol are compared with the zones of every symbol.
+pub fn merge(save: &mut SaveData, imported: Vec<ZoneData>) -> ImportReport {
+    let mut report = ImportReport::default();
+    for zone in imported {
+        let mut existing =
+            save.zones().iter().filter(|z| z.shares_symbol(&zone));
+        if let Some(dup) = existing.clone().find(|z| z.same_bounds(&zone)) {
+            report.duplicates.push(dup.clone());
+        } else if let Some(other) = existing.find(|z| z.overlaps_bounds(&zone))
+        {
+            report.conflicts.push((zone, other.clone()));
+        } else {
+            save.add_zone(zone);
+            report.added += 1;
+        }
+    }
+    report
+}
+
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub added: usize,
+    /// Existing zones which were imported again.
+    pub duplicates: Vec<ZoneData>,
+    /// Imported zones paired with the existing zones they overlap.
+    pub conflicts: Vec<(ZoneData, ZoneData)>,
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Imported {} zone(s), skipped {} duplicate(s) and {} conflict(s).",
+            self.added,
+            self.duplicates.len(),
+            self.conflicts.len()
+        )?;
+        for dup in &self.duplicates {
+            writeln!(f, "Duplicate: {dup}")?;
+        }
+        for (zone, other) in &self.conflicts {
+            writeln!(f, "Conflict: {zone} overlaps {other}")?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_price(field: &str) -> Option<Price> {
+    field.parse().ok().filter(|p: &Price| p.is_positive())
+}
+
+fn rectangle_error(index: usize) -> io::Error {
+    invalid_data(format!(
+        "Drawing {}: rectangle needs two positive prices",
+        index + 1
+    ))
+}
+
+fn parse_priority(field: &str) -> Option<PriorityData> {
+    match field.to_ascii_lowercase().as_str() {
+        "high" | "1" => Some(PriorityData::High),
+        "medium" | "2" => Some(PriorityData::Medium),
+        "low" | "3" => Some(PriorityData::Low),
+        _ => None,
+    }
+}
+
+/// Removes the exchange prefix, e.g. `BINANCE:ETHUSDT` becomes `ETHUSDT`.
+fn normalize_symbol(symbol: &str) -> String {
+    Symbol::new(symbol).into()
+}
+
+fn non_empty(field: &str) -> Option<String> {
+    let field = field.trim();
+    (!field.is_empty()).then(|| field.to_owned())
+}
+
+/// Splits the line by commas outside of double quotes.
+/// Two double quotes inside of a quoted field are an escaped quote.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        let field = fields.last_mut().unwrap();
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            _ => field.push(c),
+        }
+    }
+    fields.iter().map(|f| f.trim().to_owned()).collect()
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Shape of the TradingView drawing export.
+mod tv {
+    use crate::price::Price;
+
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    pub enum Export {
+        List(Vec<Drawing>),
+        Object {
+            #[serde(default)]
+            drawings: Vec<Drawing>,
+            #[serde(default)]
+            sources: Vec<Drawing>,
+        },
+    }
+
+    impl Export {
+        pub fn drawings(self) -> Vec<Drawing> {
+            match self {
+                Export::List(drawings) => drawings,
+                Export::Object {
+                    mut drawings,
+                    sources,
+                } => {
+                    drawings.extend(sources);
+                    drawings
+                }
+            }
+        }
+    }
+
+    #[derive(serde::Deserialize)]
+    pub struct Drawing {
+        #[serde(rename = "type")]
+        pub kind: String,
+        pub symbol: Option<String>,
+        #[serde(default)]
+        pub points: Vec<Point>,
+        #[serde(default)]
+        pub state: State,
+    }
+
+    #[derive(serde::Deserialize)]
+    pub struct Point {
+        pub price: Price,
+    }
+
+    #[derive(Default, serde::Deserialize)]
+    pub struct State {
+        pub text: Option<String>,
+        pub priority: Option<String>,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge, parse_csv, parse_tradingview};
+    use crate::{
+        price::Price,
+        save::{PriorityData, SaveData},
+    };
+
+    #[test]
+    fn csv_rows_become_zones() {
+        let csv = "symbol,low,high,priority,note\n\
+                   ETHUSDT,1200,1250,high,\"weekly open, retested\"\n\
+                   BINANCE:btcusdt,16000,16500,3,,range; weekly\n";
+        let zones = parse_csv(csv).unwrap();
+
+        assert_eq!(zones.len(), 2);
+        assert_eq!(zones[0].priority, PriorityData::High);
+        assert_eq!(zones[0].note.as_deref(), Some("weekly open, retested"));
+        assert_eq!(zones[1].symbol.as_deref(), Some("BTCUSDT"));
+        assert_eq!(zones[1].note, None);
+        assert_eq!(zones[1].tags, ["range", "weekly"]);
+
+        let err = parse_csv("ETHUSDT,1300,1250,high").unwrap_err();
+        assert!(err.to_string().contains("Line 1"));
+    }
+
+    #[test]
+    fn tradingview_rectangles_become_zones() {
+        let json = r#"{"sources": [
+            {"type": "LineToolRectangle", "symbol": "BINANCE:ETHUSDT",
+             "points": [{"time": 1, "price": 1250}, {"time": 2, "price": 1200}],
+             "state": {"text": "FOMC wick"}},
+            {"type": "LineToolTrendLine", "points": []}
+        ]}"#;
+        let zones = parse_tradingview(json).unwrap();
+
+        assert_eq!(zones.len(), 1);
+        assert_eq!(zones[0].low.0, Price::new(1200, 0));
+        assert_eq!(zones[0].high.0, Price::new(1250, 0));
+        assert_eq!(zones[0].priority, PriorityData::Medium);
+        assert_eq!(zones[0].note.as_deref(), Some("FOMC wick"));
+    }
+
+    #[test]
+    fn merge_skips_duplicates_and_conflicts() {
+        let mut save: SaveData = serde_json::from_str(
+            r#"{"zones": [{"symbol": "ETHUSDT", "priority": "High",
+                           "high": 1250.0, "low": 1200.0}]}"#,
+        )
+        .unwrap();
+        let imported = parse_csv(
+            "ETHUSDT,1200,1250,low\n\
+             ETHUSDT,1240,1300,low\n\
+             ETHUSDT,1400,1450,low\n\
+             BTCUSDT,1200,1250,low\n",
+        )
+        .unwrap();
+        let report = merge(&mut save, imported);
+
+        assert_eq!(report.added, 2);
+        assert_eq!(report.duplicates.len(), 1);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(save.zones().len(), 3);
+
+        // A zone without a symbol applies to every symbol.
+        let imported = parse_csv(
+            ",1400,1450,high\n\
+             ,1180,1210,high\n\
+             ,2000,2050,high\n\
+             SOLUSDT,2000,2050,low\n\
+             SOLUSDT,2040,2100,low\n",
+        )
+        .unwrap();
+        let report = merge(&mut save, imported);
+
+        assert_eq!(report.added, 1);
+        assert_eq!(report.duplicates.len(), 2);
+        assert_eq!(report.duplicates[0].symbol.as_deref(), Some("ETHUSDT"));
+        assert_eq!(report.conflicts.len(), 2);
+        assert_eq!(save.zones().len(), 4);
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+/// Index of the zone bounds for fast lookups of the zones containing a price
+/// and the nearest zones above and below it.
+///
+/// The lows and the highs are kept in two sorted lists. Zones containing a
+/// price are searched only among the zones whose low is at most the widest
+/// zone below the price, so the lookup stays logarithmic as long as the zones
+/// don't span most of the price range. Inserting and removing a zone shifts
+/// the lists which is cheap for thousands of zones.
+#[derive(Debug, Default, Clone)]
+pub struct ZoneIndex {
+    /// `(low, id)` sorted by the low.
+    lows: Vec<(f64, usize)>,
+    /// `(high, id)` sorted by the high.
+    highs: Vec<(f64, usize)>,
+    bounds: HashMap<usize, (f64, f64)>,
+    /// Widest zone in the index.
+    max_width: f64,
+}
+
+impl ZoneIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds the index from `(id, low, high)` triples at once.
+    pub fn from_zones<I: IntoIterator<Item = (usize, f64, f64)>>(
+        zones: I,
+    ) -> Self {
+        let mut index = Self::new();
+        for (id, low, high) in zones {
+            index.lows.push((low, id));
+            index.highs.push((high, id));
+            index.bounds.insert(id, (low, high));
+            index.max_width = index.max_width.max(high - low);
+        }
+        index.lows.sort_by(compare);
+        index.highs.sort_by(compare);
+        index
+    }
+
+    /// Adds the zone or moves it to the new bounds if the `id` is indexed.
+    pub fn insert(&mut self, id: usize, low: f64, high: f64) {
+        self.remove(id);
+        let at = self
+            .lows
+            .partition_point(|e| compare(e, &(low, id)).is_lt());
+        self.lows.insert(at, (low, id));
+        let at = self
+            .highs
+            .partition_point(|e| compare(e, &(high, id)).is_lt());
+        self.highs.insert(at, (high, id));
+        self.bounds.insert(id, (low, high));
+        self.max_width = self.max_width.max(high - low);
+    }
+
+    /// Removes the zone and returns whether it was indexed.
+    pub fn remove(&mut self, id: usize) -> bool {
+        let Some((low, high)) = self.bounds.remove(&id) else {
+            return false;
+        };
+        remove_entry(&mut self.lows, (low, id));
+        remove_entry(&mut self.highs, (high, id));
+        if high - low >= self.max_width {
+            self.max_width = self
+                .bounds
+                .values()
+                .map(|(low, high)| high - low)
+                .fold(0.0, f64::max);
+        }
+        true
+    }
+
+    /// Ids of the zones overlapping the `from` - `to` range, bounds included,
+    /// in the order of their lows.
+    pub fn overlapping(&self, from: f64, to: f64) -> Vec<usize> {
+        let first = self
+            .lows
+            .partition_point(|&(low, _)| low < from - self.max_width);
+        let last = self.lows.partition_point(|&(low, _)| low <= to);
+        self.lows[first..last.max(first)]
+            .iter()
+            .filter(|(_, id)| self.bounds[id].1 >= from)
+            .map(|&(_, id)| id)
+            .collect()
+    }
+
+    /// Ids of the zones containing the `price`.
+    pub fn containing(&self, price: f64) -> Vec<usize> {
+        self.overlapping(price, price)
+    }
+
+    /// Zone with the lowest low above the `price` and its low.
+    pub fn nearest_above(&self, price: f64) -> Option<(f64, usize)> {
+        let above = self.lows.partition_point(|&(low, _)| low <= price);
+        self.lows.get(above).copied()
+    }
+
+    /// Zone with the highest high below the `price` and its high.
+    pub fn nearest_below(&self, price: f64) -> Option<(f64, usize)> {
+        let below = self.highs.partition_point(|&(high, _)| high < price);
+        below.checked_sub(1).map(|i| self.highs[i])
+    }
+}
+
+fn compare(a: &(f64, usize), b: &(f64, usize)) -> std::cmp::Ordering {
+    a.0.total_cmp(&b.0).then(a.1.cmp(&b.1))
+}
+
+fn remove_entry(entries: &mut Vec<(f64, usize)>, entry: (f64, usize)) {
+    if let Ok(at) = entries.binary_search_by(|e| compare(e, &entry)) {
+        entries.remove(at);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ZoneIndex;
+
+    /// Looks up the same as the index by scanning all the zones.
+    fn linear(zones: &[(usize, f64, f64)], price: f64) -> Vec<usize> {
+        let mut ids: Vec<usize> = zones
+            .iter()
+            .filter(|(_, low, high)| *low <= price && price <= *high)
+            .map(|(id, ..)| *id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    #[test]
+    fn lookups_match_linear_scan() {
+        // Overlapping and nested zones of different widths.
+        let zones: Vec<(usize, f64, f64)> = (0..300)
+            .map(|i| {
+                let low = (i * 37 % 1000) as f64;
+                (i, low, low + (i % 7) as f64 * 3.0)
+            })
+            .collect();
+        let index = ZoneIndex::from_zones(zones.iter().copied());
+
+        for price in (0..1030).map(|p| p as f64 + 0.5) {
+            let mut found = index.containing(price);
+            found.sort_unstable();
+            assert_eq!(found, linear(&zones, price), "price {price}");
+
+            let above = zones
+                .iter()
+                .filter(|z| z.1 > price)
+                .map(|z| z.1)
+                .fold(f64::NAN, f64::min);
+            assert_eq!(index.nearest_above(price).map(|z| z.0), {
+                (!above.is_nan()).then_some(above)
+            });
+        }
+    }
+
+    #[test]
+    fn insert_and_remove_update_lookups() {
+        let mut index = ZoneIndex::new();
+        index.insert(0, 100.0, 200.0);
+        index.insert(1, 300.0, 310.0);
+        assert_eq!(index.containing(150.0), [0]);
+        assert_eq!(index.nearest_above(250.0), Some((300.0, 1)));
+
+        // Moving the wide zone shrinks the search window again.
+        index.insert(0, 400.0, 405.0);
+        assert!(index.containing(150.0).is_empty());
+        assert_eq!(index.nearest_below(350.0), Some((310.0, 1)));
+
+        assert!(index.remove(1));
+        assert!(!index.remove(1));
+        assert_eq!(index.nearest_below(350.0), None);
+        assert_eq!(index.containing(402.0), [0]);
+    }
+}
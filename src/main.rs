@@ -1,59 +1,96 @@
 mod alert;
+mod analysis;
 mod bot;
+mod clock;
+mod config;
 mod console;
+mod depth;
+mod divergence;
+mod execution;
+mod export;
+mod feed;
+mod import;
+mod index;
 mod input;
+mod paper;
+mod price;
+mod rate_limit;
+mod risk;
 mod save;
+mod session;
+mod shutdown;
 mod strategy;
+mod symbol;
 mod ui;
+mod venue;
 
 use bot::MarketBot;
+use clock::{Clock, ScaledClock, SystemClock};
 use console::Console;
-use crossterm::{
-    event::{self, Event},
-    execute,
-    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
-};
+use crossterm::event::{self, Event};
 use std::{
-    io,
-    time::{Duration, Instant},
+    env,
+    io::{self, Write},
+    panic::{self, AssertUnwindSafe},
+    process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::channel,
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use tui::{
     backend::{self, Backend},
     Terminal,
 };
 
-use crate::console::InputMode;
-
-const DEFAULT_SYMBOL: &str = "ETHUSDT";
-const TICK_INTERVAL: Duration = Duration::from_millis(2000);
-const RESIZE_BATCH_WAIT_DURATION: Duration = Duration::from_millis(100);
+use crate::{
+    config::{Config, ConfigWatcher},
+    console::{Command, InputMode},
+    rate_limit::RateLimiter,
+    symbol::Symbol,
+    venue::VenueKind,
+};
 
-fn run<B: Backend>(
+/// Runs the bot until the user exits or the `stop` signal is set.
+/// Changes of the config file are applied as they come.
+fn run<B: Backend + Write>(
     mut console: Console<B>,
-    mut bot: MarketBot,
+    bot: &mut MarketBot,
+    clock: Arc<dyn Clock>,
+    data: &mut save::SaveData,
+    symbols: Option<&symbol::SymbolCatalog>,
+    config: &mut ConfigWatcher,
+    stop: &AtomicBool,
 ) -> Result<(), io::Error> {
-    let mut last = Instant::now();
+    let mut last = clock.now();
 
-    loop {
+    while !stop.load(Ordering::Relaxed) {
         console.render_ui()?;
 
-        let elapsed = last.elapsed();
+        let tick_interval = bot.tick_interval();
+        let elapsed = clock.elapsed(last);
         let timeout =
-            TICK_INTERVAL.checked_sub(elapsed).unwrap_or(Duration::ZERO);
+            tick_interval.checked_sub(elapsed).unwrap_or(Duration::ZERO);
 
-        if event::poll(timeout)? {
-            match event::read()? {
-                Event::FocusGained => (),
-                Event::FocusLost => (),
+        if event::poll(clock.real_duration(timeout))? {
+            let command = match event::read()? {
                 Event::Key(key) => match console.input_mode() {
                     InputMode::Editing => console.process_editing(key),
                     InputMode::Control => console.process_controls(key),
                 },
                 Event::Resize(..) => {
-                    process_resize_batch()?;
+                    process_resize_batch(config.config().resize_wait())?;
                     console.resize();
+                    None
                 }
-                _ => (),
+                _ => None,
+            };
+            if let Some(command) = command {
+                let status = execute_command(command, bot, data, symbols)?;
+                console.show_status(status);
+                console.update_ui(bot);
             }
 
             if console.should_exit() {
@@ -61,28 +98,156 @@ fn run<B: Backend>(
             }
         }
 
-        // One tick happens every second. 1 tick == 1 second
-        if elapsed >= TICK_INTERVAL {
-            //println!("{}", elapsed.as_millis());
-            last = Instant::now();
+        if elapsed >= tick_interval {
+            last = clock.now();
+
+            if let Some(reloaded) = config.poll() {
+                let status = match reloaded {
+                    Ok(config) => {
+                        configure(bot, &mut console, config);
+                        String::from("Config reloaded")
+                    }
+                    Err(e) => format!("Config error: {e}"),
+                };
+                console.show_status(Some(status));
+            }
 
             // Tick the bot. Every tick update the live price
-            // and every 5 ticks bot analyzes the price.
+            // and every few ticks bot analyzes the price.
             bot.tick();
+            if bot.take_bell() {
+                console.ring_bell()?;
+            }
+            if bot.take_zones_changed() {
+                data.sync_zones(
+                    bot.get_symbol().as_str(),
+                    bot.zone_strat().zones(),
+                );
+                if let Some(status) = save::save_data(data)? {
+                    console.show_status(Some(status));
+                }
+            }
 
             // Update the UI with fresh market data.
-            console.update_ui(&bot);
+            console.update_ui(bot);
         }
     }
-
     Ok(())
 }
 
+/// Applies the `config` to the running bot and the console.
+fn configure<B: Backend>(
+    bot: &mut MarketBot,
+    console: &mut Console<B>,
+    config: &Config,
+) {
+    bot.set_tick_interval(config.tick_interval());
+    bot.set_analysis_ticks(config.market.analysis_ticks);
+    bot.set_alert_defaults(config.alerts.defaults());
+    console.configure(config.ui, config.theme);
+}
+
+/// Executes the user's `command`.
+/// Returns the message for the user if the command failed or the save
+/// file had to be created again.
+fn execute_command(
+    command: Command,
+    bot: &mut MarketBot,
+    data: &mut save::SaveData,
+    symbols: Option<&symbol::SymbolCatalog>,
+) -> io::Result<Option<String>> {
+    match command {
+        Command::DetectZones => bot.detect_zones(),
+        Command::AcceptSuggestion(i) => {
+            if let Some(accepted) = bot.accept_suggestion(i, data.next_index())
+            {
+                data.add_zone(save::ZoneData::detected(
+                    bot.get_symbol().as_str(),
+                    &accepted.zone,
+                ));
+                return save::save_data(data);
+            }
+        }
+        Command::RejectSuggestion(i) => bot.reject_suggestion(i),
+        Command::EditZone(i, low, high) => {
+            if let Err(e) = bot.edit_zone(i, low, high) {
+                return Ok(Some(format!("Edit failed: {e}")));
+            }
+            data.sync_zones(
+                bot.get_symbol().as_str(),
+                bot.zone_strat().zones(),
+            );
+            return save::save_data(data);
+        }
+        Command::ConfirmOrder => {
+            return Ok(Some(match bot.confirm_order() {
+                Ok(order) => format!("Order {:?}", order.status),
+                Err(e) => format!("Order failed: {e}"),
+            }));
+        }
+        Command::RejectOrder => bot.reject_order(),
+        Command::KillSwitch(killed) => bot.set_kill_switch(killed),
+        Command::SwitchSymbol(name) => {
+            return switch_symbol(&name, bot, data, symbols).map(Some);
+        }
+        Command::FindSymbol(query) => {
+            let Some(symbols) = symbols else {
+                return Ok(Some(String::from("Symbol list is not available")));
+            };
+            let found: Vec<String> = symbols
+                .search(&query, 5)
+                .into_iter()
+                .map(|info| match info.is_trading() {
+                    true => info.symbol.to_string(),
+                    false => format!("{} ({})", info.symbol, info.status),
+                })
+                .collect();
+            return Ok(Some(if found.is_empty() {
+                format!("No symbol matches {query}")
+            } else {
+                format!("Found: {}", found.join(", "))
+            }));
+        }
+    }
+    Ok(None)
+}
+
+/// Switches the bot to the `name` symbol with its saved zones and alert
+/// history. The symbol has to be trading if the exchange info is available.
+/// Returns the message for the user.
+fn switch_symbol(
+    name: &str,
+    bot: &mut MarketBot,
+    data: &save::SaveData,
+    symbols: Option<&symbol::SymbolCatalog>,
+) -> io::Result<String> {
+    let info = match symbols.map(|s| s.validate(name)) {
+        Some(Ok(info)) => Some(info.clone()),
+        Some(Err(e)) => return Ok(e.to_string()),
+        None => None,
+    };
+    let symbol = Symbol::new(name);
+    let (now, unix_time) = (bot.clock().now(), bot.clock().unix_time());
+    let history = save::load_alert_log()?
+        .iter()
+        .filter(|record| record.symbol == symbol.as_str())
+        .map(|record| record.to_alert(now, unix_time))
+        .collect();
+    let zones = data.data(symbol.as_str());
+    if let Err(e) = bot.switch_symbol(symbol.clone(), zones, history, None) {
+        return Ok(format!("Switch failed: {e}"));
+    }
+    if let Some(info) = info {
+        bot.set_symbol_info(info);
+    }
+    Ok(format!("Switched to {symbol}"))
+}
+
 /// When the user resizes the terminal, resize events come in batches meaning
 /// events returned while resizing the window aren't as important as the last
 /// resize event giving us the final terminal dimensions.
-fn process_resize_batch() -> Result<(), io::Error> {
-    while let Ok(true) = event::poll(RESIZE_BATCH_WAIT_DURATION) {
+fn process_resize_batch(wait: Duration) -> Result<(), io::Error> {
+    while let Ok(true) = event::poll(wait) {
         match event::read()? {
             Event::Resize(..) => (),
             _ => break,
@@ -91,12 +256,444 @@ fn process_resize_batch() -> Result<(), io::Error> {
     Ok(())
 }
 
+/// Command line options.
+#[derive(Debug, Default)]
+struct Args {
+    /// Config file to read instead of the one next to the executable.
+    config: Option<String>,
+    /// Exchange to read the live market from.
+    venue: Option<VenueKind>,
+    /// Pair to trade when reading the live market.
+    symbol: Option<String>,
+    /// Milliseconds between two price reads.
+    tick_interval: Option<u64>,
+    /// File to record the price updates to.
+    record: Option<String>,
+    /// Recorded session to replay instead of reading the live market.
+    replay: Option<String>,
+    /// How many times faster than the real time to replay the session.
+    speed: Option<f64>,
+    /// Recorded order book snapshots replayed along with the session.
+    depth: Option<String>,
+    /// CSV or TradingView drawing file to import the zones from.
+    import: Option<String>,
+    /// CSV or JSON file to export the zones to.
+    export_zones: Option<String>,
+    /// CSV or JSON file to export the alert history to.
+    export_alerts: Option<String>,
+    /// Markdown file to write the daily summary to.
+    export_summary: Option<String>,
+    /// Day of the summary in days since the Unix epoch.
+    date: Option<u64>,
+    /// Number of breaks after which a zone is invalidated.
+    max_breaks: Option<u32>,
+    /// Merge the overlapping zones of the same priority on load.
+    merge_overlapping: bool,
+    /// Distance from a zone which raises the approach alert.
+    proximity: Option<strategy::Proximity>,
+    /// Value of each paper trading position in the quote currency.
+    paper_size: Option<f64>,
+    /// Balance the paper trading starts with.
+    paper_balance: Option<f64>,
+    /// Smallest price step of the symbol.
+    tick_size: Option<price::Price>,
+    /// Account value the risk calculator sizes the positions for.
+    account_size: Option<f64>,
+    /// Part of the account risked per trade in percent.
+    risk_percent: Option<f64>,
+    /// Turn the alerts into orders after the user confirms them.
+    execute: bool,
+    /// Send the orders to the exchange instead of only logging them.
+    live: bool,
+    order_type: Option<execution::OrderKind>,
+    /// Value of each order in the quote currency.
+    order_size: Option<f64>,
+    max_position: Option<f64>,
+    max_daily_loss: Option<f64>,
+    /// Exchange API to send the orders to instead of Binance.
+    exchange_url: Option<String>,
+}
+
+impl Args {
+    const USAGE: &'static str = "Usage: zone-bot [--config <file.toml>] \
+        [--venue <binance|bybit|kraken>] [--symbol <pair>] [--tick-interval <ms>] \
+        [--record <file>] \
+        [--replay <file> [--speed <x>] [--depth <file.jsonl>]] \
+        [--import <file.csv|file.json>] \
+        [--export-zones <file.csv|file.json>] \
+        [--export-alerts <file.csv|file.json>] \
+        [--export-summary <file.md> [--date <YYYY-MM-DD>]] \
+        [--max-breaks <n>] [--merge-overlapping] \
+        [--proximity <price|percent%|multipleATR>] [--tick-size <price>] \
+        [--paper-size <amount>] [--paper-balance <amount>] \
+        [--account-size <amount>] [--risk-percent <percent>] \
+        [--execute [--live] [--order-type <market|limit>] \
+        [--order-size <amount>] [--max-position <amount>] \
+        [--max-daily-loss <amount>] [--exchange-url <url>]]";
+
+    fn parse() -> Result<Self, String> {
+        let mut args = Args::default();
+        let mut iter = env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            let mut value = || {
+                iter.next()
+                    .ok_or_else(|| format!("Missing value for {arg}"))
+            };
+            match arg.as_str() {
+                "--config" => args.config = Some(value()?),
+                "--venue" => args.venue = Some(value()?.parse()?),
+                "--symbol" => args.symbol = Some(value()?),
+                "--tick-interval" => {
+                    let interval = value()?;
+                    match interval.parse::<u64>() {
+                        Ok(interval) => args.tick_interval = Some(interval),
+                        _ => {
+                            return Err(format!("Invalid interval: {interval}"))
+                        }
+                    }
+                }
+                "--record" => args.record = Some(value()?),
+                "--replay" => args.replay = Some(value()?),
+                "--depth" => args.depth = Some(value()?),
+                "--import" => args.import = Some(value()?),
+                "--export-zones" => args.export_zones = Some(value()?),
+                "--export-alerts" => args.export_alerts = Some(value()?),
+                "--export-summary" => args.export_summary = Some(value()?),
+                "--date" => {
+                    let date = value()?;
+                    match export::parse_date(&date) {
+                        Some(day) => args.date = Some(day),
+                        None => return Err(format!("Invalid date: {date}")),
+                    }
+                }
+                "--max-breaks" => {
+                    let breaks = value()?;
+                    match breaks.parse::<u32>() {
+                        Ok(breaks) if breaks > 0 => {
+                            args.max_breaks = Some(breaks)
+                        }
+                        _ => return Err(format!("Invalid breaks: {breaks}")),
+                    }
+                }
+                "--merge-overlapping" => args.merge_overlapping = true,
+                "--proximity" => args.proximity = Some(value()?.parse()?),
+                "--tick-size" => {
+                    let tick = value()?;
+                    match tick.parse::<price::Price>() {
+                        Ok(tick) if tick.is_positive() => {
+                            args.tick_size = Some(tick)
+                        }
+                        _ => return Err(format!("Invalid tick size: {tick}")),
+                    }
+                }
+                "--paper-size" => args.paper_size = Some(amount(value()?)?),
+                "--paper-balance" => {
+                    args.paper_balance = Some(amount(value()?)?)
+                }
+                "--account-size" => args.account_size = Some(amount(value()?)?),
+                "--risk-percent" => {
+                    let percent = value()?;
+                    match percent.parse::<f64>() {
+                        Ok(p) if p > 0.0 && p <= 100.0 => {
+                            args.risk_percent = Some(p)
+                        }
+                        _ => return Err(format!("Invalid percent: {percent}")),
+                    }
+                }
+                "--execute" => args.execute = true,
+                "--live" => args.live = true,
+                "--order-type" => args.order_type = Some(value()?.parse()?),
+                "--order-size" => args.order_size = Some(amount(value()?)?),
+                "--max-position" => args.max_position = Some(amount(value()?)?),
+                "--max-daily-loss" => {
+                    args.max_daily_loss = Some(amount(value()?)?)
+                }
+                "--exchange-url" => args.exchange_url = Some(value()?),
+                "--speed" => {
+                    let speed = value()?;
+                    match speed.parse::<f64>() {
+                        Ok(speed) if speed > 0.0 => args.speed = Some(speed),
+                        _ => return Err(format!("Invalid speed: {speed}")),
+                    }
+                }
+                _ => return Err(format!("Unknown argument: {arg}")),
+            }
+        }
+        if args.symbol.is_some() && args.replay.is_some() {
+            return Err(String::from(
+                "--symbol doesn't work with --replay, \
+                the session has its own symbol",
+            ));
+        }
+        if args.speed.is_some() && args.replay.is_none() {
+            return Err(String::from("--speed works only with --replay"));
+        }
+        if args.depth.is_some() && args.replay.is_none() {
+            return Err(String::from("--depth works only with --replay"));
+        }
+        if args.date.is_some() && args.export_summary.is_none() {
+            return Err(String::from(
+                "--date works only with --export-summary",
+            ));
+        }
+        let execution = args.live
+            || args.order_type.is_some()
+            || args.order_size.is_some()
+            || args.max_position.is_some()
+            || args.max_daily_loss.is_some()
+            || args.exchange_url.is_some();
+        if execution && !args.execute {
+            return Err(String::from("Order options work only with --execute"));
+        }
+        Ok(args)
+    }
+
+    /// Options which take precedence over the config file.
+    fn overrides(&self) -> config::Overrides {
+        config::Overrides {
+            venue: self.venue,
+            symbol: self.symbol.clone(),
+            tick_interval_ms: self.tick_interval,
+        }
+    }
+}
+
+/// Parses a positive amount of money.
+fn amount(value: String) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(amount) if amount > 0.0 && amount.is_finite() => Ok(amount),
+        _ => Err(format!("Invalid amount: {value}")),
+    }
+}
+
+/// Creates the bot which either reads the live market
+/// or replays the recorded session.
+/// The live market symbol has to be listed and trading if the exchange info
+/// is available. A replayed session may be of a delisted pair.
+fn create_bot(
+    args: &Args,
+    config: &Config,
+    data: &save::SaveData,
+    symbols: Option<&symbol::SymbolCatalog>,
+) -> io::Result<(MarketBot, Arc<dyn Clock>)> {
+    let weight_limit = symbols
+        .and_then(|s| s.weight_limit())
+        .unwrap_or(RateLimiter::DEFAULT_LIMIT);
+    let (mut bot, clock, info) = match &args.replay {
+        Some(path) => {
+            let ticks = session::load_session(path)?;
+            let clock: Arc<dyn Clock> =
+                Arc::new(ScaledClock::new(args.speed.unwrap_or(1.0)));
+            let symbol = match ticks.first() {
+                Some(tick) => tick.symbol.clone(),
+                None => Symbol::new(&config.market.symbol),
+            };
+            let info = symbols.and_then(|s| s.get(&symbol)).cloned();
+            // The replayed prices and order books share the channel
+            // the same way the live ones do.
+            let (tx, reader) = channel();
+            let kind = config.market.venue;
+            session::spawn_replay(ticks, kind, clock.clone(), tx.clone());
+            let venue = kind.connect(weight_limit);
+            let mut bot = MarketBot::with_price_reader(
+                symbol.clone(),
+                data.data(symbol.as_str()),
+                reader,
+                venue,
+                clock.clone(),
+            );
+            if let Some(path) = &args.depth {
+                let snapshots = depth::load_depth(path)?;
+                depth::spawn_depth_replay(
+                    snapshots,
+                    symbol.clone(),
+                    clock.clone(),
+                    tx,
+                );
+                bot.watch_depth();
+            }
+            (bot, clock, info)
+        }
+        None => {
+            let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+            let name = config.market.symbol.as_str();
+            let info = match symbols {
+                Some(symbols) => Some(
+                    symbols
+                        .validate(name)
+                        .map_err(|e| {
+                            io::Error::new(io::ErrorKind::InvalidInput, e)
+                        })?
+                        .clone(),
+                ),
+                None => None,
+            };
+            let symbol = Symbol::new(name);
+            let zones = data.data(symbol.as_str());
+            let venue = config.market.venue.connect(weight_limit);
+            let mut bot = MarketBot::new(symbol, zones, venue, clock.clone());
+            if config.market.stream {
+                // The config accepts the streaming only on the venues
+                // which stream.
+                if let Some(stream) = config.market.venue.stream() {
+                    bot.stream_prices(stream);
+                }
+            }
+            let divergence = &config.divergence;
+            if let Some(other) = divergence.venue {
+                bot.compare_with(
+                    other.connect(weight_limit),
+                    divergence.threshold_percent,
+                );
+            }
+            (bot, clock, info)
+        }
+    };
+
+    if let Some(info) = info {
+        bot.set_symbol_info(info);
+    }
+
+    bot.set_tick_interval(config.tick_interval());
+    bot.set_analysis_ticks(config.market.analysis_ticks);
+    bot.set_alert_defaults(config.alerts.defaults());
+    if let Some(max_breaks) = args.max_breaks {
+        bot.set_max_breaks(max_breaks);
+    }
+    if let Some(proximity) = args.proximity {
+        bot.set_proximity(proximity);
+    }
+    if let Some(tick_size) = args.tick_size {
+        bot.set_tick_size(tick_size);
+    }
+    if args.paper_size.is_some() || args.paper_balance.is_some() {
+        let default = paper::PaperConfig::default();
+        bot.set_paper_config(paper::PaperConfig {
+            size: args.paper_size.unwrap_or(default.size),
+            balance: args.paper_balance.unwrap_or(default.balance),
+        });
+    }
+    if args.account_size.is_some() || args.risk_percent.is_some() {
+        let default = risk::RiskConfig::default();
+        bot.set_risk_config(risk::RiskConfig {
+            account: args.account_size.unwrap_or(default.account),
+            risk_percent: args.risk_percent.unwrap_or(default.risk_percent),
+            ..default
+        });
+    }
+    if args.execute {
+        bot.execute_orders(create_executor(args)?);
+    }
+    if let Some(path) = &args.record {
+        let recorder = session::SessionRecorder::create(path, clock.now())?;
+        bot.record_session(recorder);
+    }
+    bot.log_alerts(save::AlertLog::open()?);
+    Ok((bot, clock))
+}
+
+/// Creates the order executor which only logs the orders
+/// unless the `--live` option is set.
+fn create_executor(args: &Args) -> io::Result<execution::Executor> {
+    let default = execution::ExecutionConfig::default();
+    let config = execution::ExecutionConfig {
+        kind: args.order_type.unwrap_or(default.kind),
+        size: args.order_size.unwrap_or(default.size),
+        max_position: args.max_position.unwrap_or(default.max_position),
+        max_daily_loss: args.max_daily_loss.unwrap_or(default.max_daily_loss),
+    };
+    let exchange: Option<Box<dyn execution::Exchange>> = if args.live {
+        let key = |name: &str| {
+            env::var(name).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{name} is required with --live"),
+                )
+            })
+        };
+        Some(Box::new(execution::BinanceExchange::new(
+            key("BINANCE_API_KEY")?,
+            key("BINANCE_SECRET_KEY")?,
+            args.exchange_url.clone(),
+        )))
+    } else {
+        None
+    };
+    let mut executor = execution::Executor::new(config, exchange);
+    executor.log_orders(save::OrderLog::open()?);
+    Ok(executor)
+}
+
+/// Loads the exchange info from the cache or fetches it if the cache is
+/// older than a day. The stale cache is used if fetching fails. Replays
+/// only read the cache so they work offline.
+fn load_symbols(offline: bool) -> Option<symbol::SymbolCatalog> {
+    let cached = save::load_symbol_cache();
+    if offline {
+        return cached.ok();
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |t| t.as_secs());
+    if let Ok(catalog) = &cached {
+        if catalog.is_fresh(now) {
+            return cached.ok();
+        }
+    }
+    match symbol::SymbolCatalog::fetch(now) {
+        Ok(catalog) => {
+            if let Err(e) = save::save_symbol_cache(&catalog) {
+                eprintln!("Caching the exchange info failed: {e}");
+            }
+            Some(catalog)
+        }
+        Err(e) => {
+            eprintln!("Fetching the exchange info failed: {e}");
+            cached.ok()
+        }
+    }
+}
+
+/// Writes all the requested exports.
+/// Returns `false` if there was nothing to export.
+fn export(args: &Args, data: &save::SaveData) -> io::Result<bool> {
+    if let Some(path) = &args.export_zones {
+        export::export_zones(data.zones(), path)?;
+        println!("Zones exported to {path}");
+    }
+    if args.export_alerts.is_none() && args.export_summary.is_none() {
+        return Ok(args.export_zones.is_some());
+    }
+
+    let alerts = save::load_alert_log()?;
+    if let Some(path) = &args.export_alerts {
+        export::export_alerts(&alerts, path)?;
+        println!("Alerts exported to {path}");
+    }
+    if let Some(path) = &args.export_summary {
+        let today = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |t| t.as_secs() / (24 * 60 * 60));
+        export::export_summary(&alerts, args.date.unwrap_or(today), path)?;
+        println!("Summary written to {path}");
+    }
+    Ok(true)
+}
+
 fn main() {
-    let data = match save::load_save() {
+    let args = match Args::parse() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n{}", Args::USAGE);
+            process::exit(2);
+        }
+    };
+
+    let mut data = match save::load_save() {
         Ok(data) => data,
         Err(e) => match e.kind() {
             io::ErrorKind::NotFound => {
-                println!("The save file does not exist!");
+                println!("The save file does not exist! Creating a new one...");
                 match save::new_save() {
                     Ok(data) => data,
                     Err(e) => panic!("File creation err: {}", e),
@@ -105,28 +702,111 @@ fn main() {
             _ => panic!("An error ocurred while parsing the save file: {}", e),
         },
     };
-    // Market Zones
-    let zones = data.data();
+    for issue in data.validate() {
+        println!("{issue}");
+    }
+    if args.merge_overlapping {
+        println!("Merged {} overlapping zone(s)", data.merge_overlapping());
+    }
+    if let Some(path) = &args.import {
+        match import::import_file(path, &mut data) {
+            Ok(report) => print!("{report}"),
+            Err(e) => {
+                eprintln!("Import failed: {e}");
+                process::exit(1);
+            }
+        }
+        if let Err(e) = save::save_data(&data) {
+            eprintln!("Saving the imported zones failed: {e}");
+            process::exit(1);
+        }
+        return;
+    }
 
-    // Stdout
-    // TODO remove the unwraps and add the "?"
-    terminal::enable_raw_mode().unwrap();
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen).unwrap();
+    match export(&args, &data) {
+        Ok(true) => return,
+        Ok(false) => (),
+        Err(e) => {
+            eprintln!("Export failed: {e}");
+            process::exit(1);
+        }
+    }
 
-    // Console
-    let backend = backend::CrosstermBackend::new(stdout);
-    let terminal = Terminal::new(backend).unwrap();
-    let console = Console::new(terminal);
+    let config_path = match &args.config {
+        Some(path) => path.clone(),
+        None => save::config_path().unwrap_or_else(|e| {
+            eprintln!("Config path error: {e}");
+            process::exit(1);
+        }),
+    };
+    let mut config = match ConfigWatcher::load(&config_path, args.overrides()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid config {config_path}: {e}");
+            process::exit(2);
+        }
+    };
 
     // Market
-    let bot = MarketBot::new(DEFAULT_SYMBOL, zones);
+    // The exchange info lists the pairs of Binance, the pairs of the other
+    // venues are not validated.
+    let binance = config.config().market.venue == VenueKind::Binance;
+    let symbols = if binance || args.replay.is_some() {
+        load_symbols(args.replay.is_some())
+    } else {
+        None
+    };
+    let created = create_bot(&args, config.config(), &data, symbols.as_ref());
+    let (bot, clock) = match created {
+        Ok(bot) => bot,
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => {
+            eprintln!("{e}");
+            process::exit(2);
+        }
+        Err(e) => panic!("Session, log or API key error: {e}"),
+    };
 
-    match run(console, bot) {
-        Ok(_) => (),
-        Err(err) => panic!("IO Error: {err}"),
+    let stop = match shutdown::stop_signal() {
+        Ok(stop) => stop,
+        Err(e) => {
+            eprintln!("Signal handling failed: {e}");
+            process::exit(1);
+        }
     };
+    shutdown::install_panic_hook();
+    let symbols = symbols.as_ref();
+    if let Err(e) = start_console(bot, clock, data, symbols, &mut config, &stop)
+    {
+        eprintln!("IO Error: {e}");
+        process::exit(1);
+    }
+}
 
-    terminal::disable_raw_mode().unwrap();
-    execute!(io::stdout(), LeaveAlternateScreen).unwrap();
+/// Runs the bot in the terminal. Whether the bot stops normally, fails or
+/// panics, the terminal is restored, the pending alerts are logged,
+/// the zones are saved and the price reader is stopped.
+fn start_console(
+    mut bot: MarketBot,
+    clock: Arc<dyn Clock>,
+    mut data: save::SaveData,
+    symbols: Option<&symbol::SymbolCatalog>,
+    config: &mut ConfigWatcher,
+    stop: &AtomicBool,
+) -> io::Result<()> {
+    shutdown::enter_terminal()?;
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let backend = backend::CrosstermBackend::new(io::stdout());
+        let mut console = Console::new(Terminal::new(backend)?);
+        console.configure(config.config().ui, config.config().theme);
+        run(console, &mut bot, clock, &mut data, symbols, config, stop)
+    }));
+    shutdown::restore_terminal();
+
+    bot.shut_down();
+    data.sync_zones(bot.get_symbol().as_str(), bot.zone_strat().zones());
+    let saved = save::save_data(&data).map(|_| ());
+    match result {
+        Ok(result) => result.and(saved),
+        Err(panic) => panic::resume_unwind(panic),
+    }
 }
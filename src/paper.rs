@@ -0,0 +1,252 @@
+use crate::{
+    alert::{Alert, Position},
+    bot::PriceLevel,
+};
+
+/// Settings of the simulated trading.
+#[derive(Debug, Clone, Copy)]
+pub struct PaperConfig {
+    /// Value of each opened position in the quote currency.
+    pub size: f64,
+    /// Equity the simulation starts with.
+    pub balance: f64,
+}
+
+impl Default for PaperConfig {
+    fn default() -> Self {
+        Self {
+            size: 100.0,
+            balance: 10_000.0,
+        }
+    }
+}
+
+/// Opens simulated positions from the alerts to measure how the zone
+/// strategy would have performed. Each position is stopped out at the far
+/// side of its zone and takes the profit at the next zone.
+#[derive(Debug)]
+pub struct PaperTrader {
+    config: PaperConfig,
+    open: Vec<PaperPosition>,
+    closed: Vec<PaperPosition>,
+    /// Latest price the positions were tracked with.
+    price: PriceLevel,
+}
+
+impl PaperTrader {
+    pub fn new(config: PaperConfig) -> Self {
+        Self {
+            config,
+            open: Vec::new(),
+            closed: Vec::new(),
+            price: PriceLevel::NAN,
+        }
+    }
+
+    /// Opens the position suggested by the `alert` with the take-profit at
+    /// the `target`, [`PriceLevel::NAN`] if there is no zone to take the
+    /// profit at. Alerts without a zone, alerts of zones which already have
+    /// an open position and alerts whose stop-loss or take-profit lies on
+    /// the wrong side of the price are ignored.
+    /// Returns whether the position was opened.
+    pub fn open(&mut self, alert: &Alert, target: PriceLevel) -> bool {
+        let (Some(zone), entry) = (alert.zone(), alert.price()) else {
+            return false;
+        };
+        if entry.0.is_nan() || entry.0 <= 0.0 {
+            return false;
+        }
+        let stop = match alert.position() {
+            Position::Buy => zone.low.level(),
+            Position::Sell => zone.high.level(),
+        };
+        let position = PaperPosition {
+            position: alert.position(),
+            entry,
+            quantity: self.config.size / entry.0,
+            stop,
+            target: (!target.0.is_nan()).then_some(target),
+            exit: None,
+        };
+        let valid = position.gain(stop) < 0.0
+            && position.target.is_none_or(|t| position.gain(t) > 0.0);
+        let duplicate = self.open.iter().any(|p| {
+            p.position == position.position && p.stop.0 == position.stop.0
+        });
+        if !valid || duplicate {
+            return false;
+        }
+        self.open.push(position);
+        true
+    }
+
+    /// Closes the positions whose stop-loss or take-profit
+    /// the `price` reached.
+    pub fn track(&mut self, price: PriceLevel) {
+        if price.0.is_nan() {
+            return;
+        }
+        self.price = price;
+        let (closed, open) = std::mem::take(&mut self.open)
+            .into_iter()
+            .partition(|p| p.should_close(price));
+        self.open = open;
+        for mut position in closed {
+            position.exit = Some(price);
+            self.closed.push(position);
+        }
+    }
+
+    /// Closes all the open positions at the latest price, e.g. before
+    /// the prices of another symbol are tracked.
+    pub fn close_all(&mut self) {
+        let exit = (!self.price.0.is_nan()).then_some(self.price);
+        for mut position in std::mem::take(&mut self.open) {
+            position.exit = exit;
+            self.closed.push(position);
+        }
+        self.price = PriceLevel::NAN;
+    }
+
+    pub fn open_positions(&self) -> &[PaperPosition] {
+        &self.open
+    }
+
+    pub fn closed_positions(&self) -> &[PaperPosition] {
+        &self.closed
+    }
+
+    /// Profit of the closed positions.
+    pub fn realized_pnl(&self) -> f64 {
+        self.closed
+            .iter()
+            .filter_map(PaperPosition::realized_pnl)
+            .sum()
+    }
+
+    /// Profit of the open positions at the latest price.
+    pub fn unrealized_pnl(&self) -> f64 {
+        if self.price.0.is_nan() {
+            return 0.0;
+        }
+        self.open.iter().map(|p| p.pnl(self.price)).sum()
+    }
+
+    /// Starting balance with the realized and the unrealized profit.
+    pub fn equity(&self) -> f64 {
+        self.config.balance + self.realized_pnl() + self.unrealized_pnl()
+    }
+
+    /// Equity change since the start in percent.
+    pub fn equity_change(&self) -> f64 {
+        (self.equity() - self.config.balance) / self.config.balance * 100.0
+    }
+
+    /// Number of the closed positions which made a profit.
+    pub fn wins(&self) -> usize {
+        self.closed
+            .iter()
+            .filter(|p| p.realized_pnl().is_some_and(|pnl| pnl > 0.0))
+            .count()
+    }
+}
+
+/// Simulated position opened from an alert.
+#[derive(Debug, Clone)]
+pub struct PaperPosition {
+    pub position: Position,
+    pub entry: PriceLevel,
+    /// Amount of the base currency.
+    pub quantity: f64,
+    /// Far side of the zone which caused the alert.
+    pub stop: PriceLevel,
+    /// Nearest side of the next zone in the direction of the position.
+    pub target: Option<PriceLevel>,
+    /// Price the position was closed at.
+    pub exit: Option<PriceLevel>,
+}
+
+impl PaperPosition {
+    /// Price move from the entry to the `price` in the direction
+    /// of the position.
+    fn gain(&self, price: PriceLevel) -> f64 {
+        match self.position {
+            Position::Buy => price.0 - self.entry.0,
+            Position::Sell => self.entry.0 - price.0,
+        }
+    }
+
+    fn should_close(&self, price: PriceLevel) -> bool {
+        self.gain(price) <= self.gain(self.stop)
+            || self
+                .target
+                .is_some_and(|t| self.gain(price) >= self.gain(t))
+    }
+
+    /// Profit of the position if it was closed at the `price`.
+    pub fn pnl(&self, price: PriceLevel) -> f64 {
+        self.gain(price) * self.quantity
+    }
+
+    /// Profit of the position once it is closed.
+    pub fn realized_pnl(&self) -> Option<f64> {
+        self.exit.map(|price| self.pnl(price))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::{PaperConfig, PaperTrader};
+    use crate::{
+        alert::{Alert, AlertZone, Position},
+        bot::PriceLevel,
+        price::Price,
+        strategy::ZonePriority,
+    };
+
+    fn alert(position: Position, price: f64) -> Alert {
+        Alert::new(Instant::now(), PriceLevel(price), position, String::new())
+            .with_zone(AlertZone {
+                priority: ZonePriority::High,
+                low: Price::new(1000, 0),
+                high: Price::new(1100, 0),
+            })
+    }
+
+    #[test]
+    fn positions_close_at_stop_and_target() {
+        let mut trader = PaperTrader::new(PaperConfig::default());
+
+        assert!(trader.open(&alert(Position::Buy, 1050.0), PriceLevel(1200.0)));
+        // Only one position per zone and direction.
+        assert!(!trader.open(&alert(Position::Buy, 1060.0), PriceLevel::NAN));
+        assert!(trader.open(&alert(Position::Sell, 1050.0), PriceLevel(900.0)));
+
+        trader.track(PriceLevel(1155.0));
+        assert_eq!(trader.open_positions().len(), 1);
+        assert_eq!(trader.closed_positions()[0].position, Position::Sell);
+        assert!(trader.unrealized_pnl() > 0.0);
+
+        trader.track(PriceLevel(1210.0));
+        assert!(trader.open_positions().is_empty());
+        assert_eq!(trader.wins(), 1);
+        let pnl = 100.0 / 1050.0 * (160.0 - 105.0);
+        assert!((trader.realized_pnl() - pnl).abs() < 1e-9);
+        assert!((trader.equity() - 10_000.0 - pnl).abs() < 1e-9);
+    }
+
+    #[test]
+    fn positions_with_stop_beyond_price_are_ignored() {
+        let mut trader = PaperTrader::new(PaperConfig::default());
+
+        // Price below the support, the stop would be above the entry.
+        assert!(!trader.open(&alert(Position::Buy, 990.0), PriceLevel::NAN));
+        // Target behind the entry.
+        assert!(
+            !trader.open(&alert(Position::Sell, 1050.0), PriceLevel(1200.0))
+        );
+        assert!(trader.open_positions().is_empty());
+    }
+}
@@ -0,0 +1,283 @@
+use std::{fmt, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::bot::PriceLevel;
+
+/// Exact decimal price with up to [`Price::DECIMALS`] decimals, the
+/// precision of the exchange prices. Zone bounds are kept as [`Price`] so
+/// they don't drift when they are saved, loaded or compared, while the
+/// calculations on the live price use the floating [`PriceLevel`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Price(i64);
+
+impl Price {
+    pub const DECIMALS: u32 = 8;
+    const SCALE: i64 = 10i64.pow(Self::DECIMALS);
+
+    /// Price of `units` of the smallest decimal, e.g. `Price::new(125, 2)`
+    /// is `1.25`.
+    pub const fn new(units: i64, decimals: u32) -> Self {
+        Self(units * 10i64.pow(Self::DECIMALS - decimals))
+    }
+
+    /// Rounds the `value` to [`Price::DECIMALS`] decimals. Every price with
+    /// at most that many decimals converted to `f64` converts back exactly.
+    /// Returns `None` if the `value` is not a number or is too big.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        let units = (value * Self::SCALE as f64).round();
+        (units.is_finite() && units.abs() < i64::MAX as f64)
+            .then_some(Self(units as i64))
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / Self::SCALE as f64
+    }
+
+    pub fn level(self) -> PriceLevel {
+        PriceLevel(self.to_f64())
+    }
+
+    pub fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+
+    /// Rounds to the nearest multiple of the `tick` size,
+    /// halves away from zero.
+    pub fn round_to(self, tick: Price) -> Self {
+        if tick.0 <= 0 {
+            return self;
+        }
+        let rounded = (self.0.abs() + tick.0 / 2) / tick.0 * tick.0;
+        Self(rounded * self.0.signum())
+    }
+
+    /// Rounds down to a multiple of the `step`, e.g. the order quantity
+    /// to the lot size of the symbol.
+    pub fn floor_to(self, step: Price) -> Self {
+        if step.0 <= 0 {
+            return self;
+        }
+        Self(self.0.div_euclid(step.0) * step.0)
+    }
+
+    /// Number of decimals needed to write the price exactly,
+    /// e.g. 2 for the tick size of `0.01`.
+    pub fn decimals(self) -> u32 {
+        let mut decimals = Self::DECIMALS;
+        let mut units = self.0;
+        while decimals > 0 && units % 10 == 0 {
+            units /= 10;
+            decimals -= 1;
+        }
+        decimals
+    }
+
+    /// Writes the price with exactly as many decimals as the `tick` size
+    /// has or with as many as needed if the tick size is not known.
+    pub fn format(self, tick: Option<Price>) -> String {
+        match tick {
+            Some(tick) => format!("{:.*}", tick.decimals() as usize, self),
+            None => self.to_string(),
+        }
+    }
+}
+
+/// Writes the price level like the [`Price`] it rounds to,
+/// `-` if the price is not known.
+pub fn format_level(level: PriceLevel, tick: Option<Price>) -> String {
+    match Price::from_f64(level.0) {
+        Some(price) => price.format(tick),
+        None => String::from("-"),
+    }
+}
+
+impl From<Price> for PriceLevel {
+    fn from(price: Price) -> Self {
+        price.level()
+    }
+}
+
+impl fmt::Display for Price {
+    /// Writes all the decimals up to the precision or only the needed ones
+    /// if the precision is not set. Longer precisions are padded with zeros
+    /// and shorter ones round the price.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let decimals = match f.precision() {
+            Some(precision) => precision as u32,
+            None => self.decimals(),
+        };
+        let kept = decimals.min(Self::DECIMALS);
+        let price = self.round_to(Self::new(1, kept));
+        let sign = if price.0 < 0 { "-" } else { "" };
+        let (whole, fraction) =
+            (price.0.abs() / Self::SCALE, price.0.abs() % Self::SCALE);
+        write!(f, "{sign}{whole}")?;
+        if decimals > 0 {
+            let fraction = fraction / 10i64.pow(Self::DECIMALS - kept);
+            write!(
+                f,
+                ".{fraction:0kept$}{:0<pad$}",
+                "",
+                kept = kept as usize,
+                pad = (decimals - kept) as usize
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePriceError(String);
+
+impl fmt::Display for ParsePriceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid price: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParsePriceError {}
+
+impl FromStr for Price {
+    type Err = ParsePriceError;
+
+    /// Parses the decimal price exactly. Prices with more than
+    /// [`Price::DECIMALS`] significant decimals are rejected.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParsePriceError(s.to_owned());
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let fraction = fraction.trim_end_matches('0');
+        let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty())
+            || !is_digits(whole)
+            || !is_digits(fraction)
+            || fraction.len() > Self::DECIMALS as usize
+        {
+            return Err(invalid());
+        }
+
+        let whole: i64 = match whole {
+            "" => 0,
+            whole => whole.parse().map_err(|_| invalid())?,
+        };
+        let fraction: i64 = match fraction {
+            "" => 0,
+            digits => {
+                let padding = Self::DECIMALS - digits.len() as u32;
+                digits.parse::<i64>().map_err(|_| invalid())?
+                    * 10i64.pow(padding)
+            }
+        };
+        let units = whole
+            .checked_mul(Self::SCALE)
+            .and_then(|units| units.checked_add(fraction))
+            .ok_or_else(invalid)?;
+        Ok(Self(if negative { -units } else { units }))
+    }
+}
+
+/// Prices are saved as strings so they are read back exactly.
+impl Serialize for Price {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Reads the prices saved as strings as well as the numbers
+/// the older saves used.
+impl<'de> Deserialize<'de> for Price {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        struct PriceVisitor;
+
+        impl de::Visitor<'_> for PriceVisitor {
+            type Value = Price;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a decimal price")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Price, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Price, E> {
+                Price::from_f64(v)
+                    .ok_or_else(|| E::custom(format!("Invalid price: {v}")))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Price, E> {
+                self.visit_f64(v as f64)
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Price, E> {
+                self.visit_f64(v as f64)
+            }
+        }
+
+        deserializer.deserialize_any(PriceVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Price;
+
+    fn price(s: &str) -> Price {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_and_writes_exactly() {
+        for s in ["0.00001234", "65432.1", "1200", "-3.5", "0.1"] {
+            assert_eq!(price(s).to_string(), s);
+        }
+        assert_eq!(price("65432.10"), Price::new(6543210, 2));
+        assert_eq!(price(".5"), price("0.5"));
+        for s in ["", ".", "1.2.3", "abc", "1e5", "0.000000001", "--1"] {
+            assert!(s.parse::<Price>().is_err(), "{s} parsed");
+        }
+
+        // Floats of the exchange prices round back to the same price.
+        assert_eq!(Price::from_f64(0.1 + 0.2), Some(price("0.3")));
+        assert_eq!(Price::from_f64(65432.1), Some(price("65432.1")));
+        assert_eq!(Price::from_f64(f64::NAN), None);
+    }
+
+    #[test]
+    fn rounds_and_formats_to_tick_size() {
+        let tick = price("0.01");
+        assert_eq!(tick.decimals(), 2);
+        assert_eq!(price("1234.565").round_to(tick), price("1234.57"));
+        assert_eq!(price("1234.564").round_to(tick), price("1234.56"));
+        assert_eq!(price("-0.005").round_to(tick), price("-0.01"));
+        assert_eq!(price("0.129").floor_to(tick), price("0.12"));
+        assert_eq!(price("5").round_to(price("0.5")), price("5"));
+
+        assert_eq!(price("1200").format(Some(tick)), "1200.00");
+        assert_eq!(price("0.00001234").format(Some(price("0.0001"))), "0.0000");
+        assert_eq!(price("1200.5").format(None), "1200.5");
+        assert_eq!(format!("{:.10}", price("1.5")), "1.5000000000");
+    }
+
+    #[test]
+    fn serializes_as_string_and_reads_numbers() {
+        let json = serde_json::to_string(&price("0.00001234")).unwrap();
+        assert_eq!(json, r#""0.00001234""#);
+        let read: Price = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, price("0.00001234"));
+
+        let old: Price = serde_json::from_str("1250.3").unwrap();
+        assert_eq!(old, price("1250.3"));
+        let old: Price = serde_json::from_str("1250").unwrap();
+        assert_eq!(old, price("1250"));
+    }
+}
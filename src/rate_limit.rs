@@ -0,0 +1,140 @@
+use std::time::{Duration, Instant};
+
+/// Keeps the requests within the exchange's request weight limit and backs
+/// off when the exchange rejects them. The times are real times, a replay
+/// running on a faster clock doesn't send the requests any faster.
+///
+/// The exchange counts the weight of the requests per minute and reports
+/// the weight used so far with every response. A request which would go
+/// over the limit waits for the next minute.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    /// Request weight allowed per minute.
+    limit: u64,
+    /// Weight used in the minute starting at the `window`.
+    used: u64,
+    /// Start of the minute the `used` weight belongs to, `None` before
+    /// the first request.
+    window: Option<Instant>,
+    /// No requests are sent before this time.
+    blocked_until: Option<Instant>,
+    /// Rejections in a row, each one without a retry time doubles the wait.
+    rejections: u32,
+}
+
+impl RateLimiter {
+    /// Request weight per minute Binance allows unless the exchange info
+    /// says otherwise.
+    pub const DEFAULT_LIMIT: u64 = 6000;
+    const WINDOW: Duration = Duration::from_secs(60);
+    /// Wait after the first rejection without a retry time.
+    const BACKOFF: Duration = Duration::from_secs(30);
+    const MAX_BACKOFF: Duration = Duration::from_secs(30 * 60);
+
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            used: 0,
+            window: None,
+            blocked_until: None,
+            rejections: 0,
+        }
+    }
+
+    /// Checks whether the request of the `weight` can be sent at `now`.
+    /// Returns how long to wait if it can't.
+    pub fn check(&self, weight: u64, now: Instant) -> Result<(), Duration> {
+        if let Some(until) = self.blocked_until.filter(|until| now < *until) {
+            return Err(until - now);
+        }
+        match self.window_end(now) {
+            Some(end) if self.used + weight > self.limit => Err(end - now),
+            _ => Ok(()),
+        }
+    }
+
+    /// Records the accepted request of the `weight` sent at `now`.
+    /// The `used` weight reported by the exchange takes precedence
+    /// over the weight counted here.
+    pub fn accept(&mut self, weight: u64, used: Option<u64>, now: Instant) {
+        if self.window_end(now).is_none() {
+            self.window = Some(now);
+            self.used = 0;
+        }
+        self.used = used.unwrap_or(self.used + weight);
+        self.rejections = 0;
+    }
+
+    /// Records the request rejected at `now` for sending too many requests.
+    /// Waits for `retry_after` if the exchange sent it, otherwise longer
+    /// after every rejection in a row.
+    pub fn reject(&mut self, retry_after: Option<Duration>, now: Instant) {
+        let wait = retry_after.unwrap_or_else(|| {
+            let doubled = Self::BACKOFF.saturating_mul(1 << self.rejections);
+            doubled.min(Self::MAX_BACKOFF)
+        });
+        self.rejections = (self.rejections + 1).min(16);
+        self.blocked_until = Some(now + wait);
+    }
+
+    /// End of the minute counting the weight if it is still running at
+    /// `now`.
+    fn window_end(&self, now: Instant) -> Option<Instant> {
+        let end = self.window? + Self::WINDOW;
+        (now < end).then_some(end)
+    }
+
+    /// Moves the recorded times back as if `elapsed` passed.
+    #[cfg(test)]
+    pub fn rewind(&mut self, elapsed: Duration) {
+        self.window = self.window.map(|window| window - elapsed);
+        self.blocked_until = self.blocked_until.map(|until| until - elapsed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::RateLimiter;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn requests_wait_for_next_minute_over_the_limit() {
+        let mut limiter = RateLimiter::new(100);
+        let start = Instant::now();
+        assert_eq!(limiter.check(4, start), Ok(()));
+
+        limiter.accept(4, Some(98), start);
+        assert_eq!(limiter.check(2, start + secs(1)), Ok(()));
+        assert_eq!(limiter.check(4, start + secs(31)), Err(secs(29)));
+        // The weight is counted again in the next minute.
+        assert_eq!(limiter.check(4, start + secs(60)), Ok(()));
+
+        // Without the reported weight it is counted locally.
+        limiter.accept(40, None, start + secs(60));
+        limiter.accept(40, None, start + secs(61));
+        assert_eq!(limiter.check(40, start + secs(62)), Err(secs(58)));
+    }
+
+    #[test]
+    fn rejections_back_off() {
+        let mut limiter = RateLimiter::new(100);
+        let start = Instant::now();
+        limiter.reject(Some(secs(120)), start);
+        assert_eq!(limiter.check(1, start), Err(secs(120)));
+        assert_eq!(limiter.check(1, start + secs(120)), Ok(()));
+        limiter.accept(1, None, start + secs(120));
+
+        let now = start + secs(1000);
+        limiter.reject(None, now);
+        limiter.reject(None, now);
+        assert_eq!(limiter.check(1, now), Err(secs(60)));
+        limiter.accept(1, None, now + secs(60));
+        limiter.reject(None, now + secs(100));
+        assert_eq!(limiter.check(1, now + secs(100)), Err(secs(30)));
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{alert::Position, bot::PriceLevel, strategy::Zone};
+
+/// Account settings the position size is calculated from.
+#[derive(Debug, Clone, Copy)]
+pub struct RiskConfig {
+    /// Account value in the quote currency.
+    pub account: f64,
+    /// Part of the account lost when the stop is hit, in percent.
+    pub risk_percent: f64,
+    /// Distance of the stop beyond the zone edge in percent.
+    pub stop_buffer: f64,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            account: 10_000.0,
+            risk_percent: 1.0,
+            stop_buffer: 0.1,
+        }
+    }
+}
+
+/// Trade planned at a zone: entry at the zone edge facing the price,
+/// stop beyond the opposite edge and the target at the next zone.
+#[derive(Debug, Clone, Copy)]
+pub struct RiskPlan {
+    pub side: Position,
+    pub entry: PriceLevel,
+    pub stop: PriceLevel,
+    /// `None` if there is no zone beyond this one.
+    pub target: Option<PriceLevel>,
+    /// Amount of the base currency which loses the risked amount
+    /// at the stop.
+    pub quantity: f64,
+    /// Amount of the quote currency lost at the stop.
+    pub risk: f64,
+    /// Reward at the target in multiples of the risk.
+    pub reward: Option<f64>,
+    /// Percentage distance from the price to the entry.
+    pub distance: f64,
+}
+
+impl RiskPlan {
+    /// Plans the `side` trade at the `zone`. The `target` is the nearest
+    /// edge of the next zone, [`PriceLevel::NAN`] if there is none.
+    pub fn new(
+        zone: &Zone,
+        side: Position,
+        target: PriceLevel,
+        price: PriceLevel,
+        config: &RiskConfig,
+    ) -> Self {
+        let buffer = config.stop_buffer / 100.0;
+        let (entry, stop) = match side {
+            Position::Buy => (
+                zone.high.level(),
+                PriceLevel(zone.low_f64() * (1.0 - buffer)),
+            ),
+            Position::Sell => (
+                zone.low.level(),
+                PriceLevel(zone.high_f64() * (1.0 + buffer)),
+            ),
+        };
+        let per_unit = (entry.0 - stop.0).abs();
+        let risk = config.account * config.risk_percent / 100.0;
+        let target = (!target.0.is_nan()).then_some(target);
+        Self {
+            side,
+            entry,
+            stop,
+            target,
+            quantity: risk / per_unit,
+            risk,
+            reward: target.map(|t| (t.0 - entry.0).abs() / per_unit),
+            distance: price.distance_percent(entry),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RiskConfig, RiskPlan};
+    use crate::{
+        alert::Position,
+        bot::PriceLevel,
+        price::Price,
+        strategy::{Zone, ZonePriority},
+    };
+
+    #[test]
+    fn plans_long_at_support() {
+        let zone = Zone::new(
+            ZonePriority::High,
+            Price::new(1000, 0),
+            Price::new(1050, 0),
+        );
+        let config = RiskConfig {
+            account: 5000.0,
+            risk_percent: 2.0,
+            stop_buffer: 0.5,
+        };
+        let plan = RiskPlan::new(
+            &zone,
+            Position::Buy,
+            PriceLevel(1215.0),
+            PriceLevel(1100.0),
+            &config,
+        );
+
+        assert_eq!(plan.entry.0, 1050.0);
+        assert_eq!(plan.stop.0, 995.0);
+        assert_eq!(plan.risk, 100.0);
+        assert!((plan.quantity - 100.0 / 55.0).abs() < 1e-9);
+        assert_eq!(plan.reward, Some(3.0));
+        assert!((plan.distance + 100.0 / 22.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn plans_short_at_resistance_without_target() {
+        let zone = Zone::new(
+            ZonePriority::Low,
+            Price::new(1000, 0),
+            Price::new(1100, 0),
+        );
+        let plan = RiskPlan::new(
+            &zone,
+            Position::Sell,
+            PriceLevel::NAN,
+            PriceLevel(950.0),
+            &RiskConfig::default(),
+        );
+
+        assert_eq!(plan.entry.0, 1000.0);
+        assert!((plan.stop.0 - 1101.1).abs() < 1e-9);
+        assert!(plan.target.is_none());
+        assert_eq!(plan.reward, None);
+    }
+}
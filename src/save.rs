@@ -1,11 +1,23 @@
-use std::{fs, io};
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    io::{self, Write},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 use crate::{
-    bot::PriceLevel,
-    strategy::{Zone, ZonePriority},
+    alert::{Alert, AlertRules, AlertZone, Outcome, Position},
+    execution::OrderRecord,
+    price::Price,
+    strategy::{check_bounds, Zone, ZonePriority, ZoneRole, ZoneState},
+    symbol::SymbolCatalog,
 };
 
 const SAVE: &str = "bot_data.json";
+const ALERT_LOG: &str = "bot_alerts.jsonl";
+const ORDER_LOG: &str = "bot_orders.jsonl";
+const SYMBOL_CACHE: &str = "bot_symbols.json";
+const CONFIG: &str = "bot_config.toml";
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct SaveData {
@@ -17,48 +29,334 @@ impl SaveData {
         Self { zones: Vec::new() }
     }
 
-    pub fn data(self) -> Vec<Zone> {
-        let mut zones = Vec::with_capacity(self.zones.len());
-        for z in self.zones {
-            zones.push(z.into());
+    /// Returns the zones of the `symbol` and the zones which are not tied
+    /// to any symbol, the latter with their bounds and lifecycle on the
+    /// `symbol`. Each zone remembers which saved zone it is.
+    pub fn data(&self, symbol: &str) -> Vec<Zone> {
+        self.zones
+            .iter()
+            .enumerate()
+            .filter(|(_, z)| z.symbol.as_deref().is_none_or(|s| s == symbol))
+            .map(|(i, z)| {
+                let mut data = z.clone();
+                if let Some(state) = data.symbols.remove(symbol) {
+                    data.low = state.low;
+                    data.high = state.high;
+                    data.lifecycle = state.lifecycle;
+                }
+                let mut zone = Zone::from(data);
+                zone.saved = Some(i);
+                zone
+            })
+            .collect()
+    }
+
+    pub fn zones(&self) -> &[ZoneData] {
+        &self.zones
+    }
+
+    /// Index the next added zone gets.
+    pub fn next_index(&self) -> usize {
+        self.zones.len()
+    }
+
+    /// Adds the `zone` and marks it as created now
+    /// unless it already has its creation time.
+    pub fn add_zone(&mut self, mut zone: ZoneData) {
+        if zone.lifecycle.created.is_none() {
+            zone.lifecycle.created = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .map(|t| t.as_secs());
+        }
+        self.zones.push(zone);
+    }
+
+    /// Copies the bounds and the lifecycle of the bot's `zones` of the
+    /// `symbol` back to the saved zones they were loaded from. The zones
+    /// which are not tied to any symbol keep them per symbol so the symbols
+    /// don't change each other's zones.
+    pub fn sync_zones(&mut self, symbol: &str, zones: &[Zone]) {
+        for zone in zones {
+            let Some(data) = zone.saved.and_then(|i| self.zones.get_mut(i))
+            else {
+                continue;
+            };
+            match data.symbol.as_deref() {
+                Some(s) if s == symbol => {
+                    data.low = PriceLevelData(zone.low);
+                    data.high = PriceLevelData(zone.high);
+                    data.lifecycle = LifecycleData::from(zone);
+                }
+                Some(_) => (),
+                None => {
+                    let state = SymbolZoneData {
+                        low: PriceLevelData(zone.low),
+                        high: PriceLevelData(zone.high),
+                        lifecycle: LifecycleData::from(zone),
+                    };
+                    data.symbols.insert(symbol.to_owned(), state);
+                }
+            }
+        }
+    }
+
+    /// Fixes or removes the invalid zones and returns what was wrong with
+    /// them. Inverted zones are flipped while zones without positive bounds
+    /// and duplicates are removed. Overlapping zones are only reported,
+    /// [`SaveData::merge_overlapping`] merges them.
+    pub fn validate(&mut self) -> Vec<String> {
+        let mut issues = Vec::new();
+        let mut valid: Vec<ZoneData> = Vec::with_capacity(self.zones.len());
+        for mut zone in std::mem::take(&mut self.zones) {
+            if zone.low.0 > zone.high.0 {
+                issues.push(format!("Inverted zone flipped: {zone}"));
+                std::mem::swap(&mut zone.low, &mut zone.high);
+            }
+            if let Err(e) = check_bounds(zone.low.0, zone.high.0) {
+                issues.push(format!("Zone removed, {e}: {zone}"));
+            } else if valid.iter().any(|z| z.duplicates(&zone)) {
+                issues.push(format!("Duplicate zone removed: {zone}"));
+            } else {
+                if let Some(other) = valid.iter().find(|z| z.overlaps(&zone)) {
+                    issues.push(format!("Zone {zone} overlaps {other}"));
+                }
+                valid.push(zone);
+            }
+        }
+        self.zones = valid;
+        issues
+    }
+
+    /// Merges the overlapping zones of the same symbol and priority into
+    /// one zone spanning all of them. Returns how many zones were merged
+    /// away.
+    pub fn merge_overlapping(&mut self) -> usize {
+        let mut merged = 0;
+        let mut i = 0;
+        while i < self.zones.len() {
+            let zone = &self.zones[i];
+            let into = self.zones[..i].iter().position(|other| {
+                other.priority == zone.priority && other.overlaps(zone)
+            });
+            match into {
+                Some(j) => {
+                    let zone = self.zones.remove(i);
+                    self.zones[j].merge(zone);
+                    merged += 1;
+                    // The grown zone may overlap the zones checked before.
+                    i = 0;
+                }
+                None => i += 1,
+            }
         }
-        zones
+        merged
     }
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-struct ZoneData {
-    priority: PriorityData,
-    high: PriceLevelData,
-    low: PriceLevelData,
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ZoneData {
+    /// Zones without a symbol apply to every symbol.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    pub priority: PriorityData,
+    pub high: PriceLevelData,
+    pub low: PriceLevelData,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Overrides of the global alert behavior.
+    #[serde(default, skip_serializing_if = "AlertRules::is_default")]
+    pub rules: AlertRules,
+    #[serde(flatten)]
+    pub lifecycle: LifecycleData,
+    /// Bounds and lifecycle of the zone without a symbol on each symbol
+    /// it was traded on.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub symbols: BTreeMap<String, SymbolZoneData>,
+}
+
+/// Zone without a symbol as it is on one symbol.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SymbolZoneData {
+    pub high: PriceLevelData,
+    pub low: PriceLevelData,
+    #[serde(flatten)]
+    pub lifecycle: LifecycleData,
+}
+
+/// State of the zone which changes as the price moves around it.
+#[derive(
+    Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+#[serde(default)]
+pub struct LifecycleData {
+    /// Unix time in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<u64>,
+    /// Unix time in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<ZoneRole>,
+    pub state: ZoneState,
+    pub touches: u32,
+    pub breaks: u32,
+}
+
+impl From<&Zone> for LifecycleData {
+    fn from(zone: &Zone) -> Self {
+        Self {
+            created: zone.created,
+            expires: zone.expires,
+            role: zone.role,
+            state: zone.state,
+            touches: zone.touches,
+            breaks: zone.breaks,
+        }
+    }
+}
+
+impl ZoneData {
+    /// Whether the zones of the same symbol share any price.
+    pub fn overlaps(&self, other: &ZoneData) -> bool {
+        self.symbol == other.symbol && self.overlaps_bounds(other)
+    }
+
+    /// Whether the zones of the same symbol have the same bounds.
+    pub fn duplicates(&self, other: &ZoneData) -> bool {
+        self.symbol == other.symbol && self.same_bounds(other)
+    }
+
+    /// Whether the zones apply to a common symbol. Zones without a symbol
+    /// apply to every symbol.
+    pub fn shares_symbol(&self, other: &ZoneData) -> bool {
+        self.symbol.is_none()
+            || other.symbol.is_none()
+            || self.symbol == other.symbol
+    }
+
+    pub fn overlaps_bounds(&self, other: &ZoneData) -> bool {
+        self.low.0 <= other.high.0 && other.low.0 <= self.high.0
+    }
+
+    pub fn same_bounds(&self, other: &ZoneData) -> bool {
+        self.low == other.low && self.high == other.high
+    }
+
+    /// Extends the zone over the `other` zone and combines their
+    /// notes, tags and lifecycles.
+    fn merge(&mut self, other: ZoneData) {
+        self.low.0 = self.low.0.min(other.low.0);
+        self.high.0 = self.high.0.max(other.high.0);
+        self.note = match (self.note.take(), other.note) {
+            (Some(a), Some(b)) if a != b => Some(format!("{a}; {b}")),
+            (a, b) => a.or(b),
+        };
+        for tag in other.tags {
+            if !self.tags.contains(&tag) {
+                self.tags.push(tag);
+            }
+        }
+        if self.rules.is_default() {
+            self.rules = other.rules;
+        }
+
+        let (a, b) = (&mut self.lifecycle, other.lifecycle);
+        a.created = match (a.created, b.created) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        a.expires = a.expires.zip(b.expires).map(|(a, b)| a.max(b));
+        a.role = a.role.or(b.role);
+        if b.state == ZoneState::Active {
+            a.state = ZoneState::Active;
+        }
+        a.touches += b.touches;
+        a.breaks = a.breaks.max(b.breaks);
+        for (symbol, state) in other.symbols {
+            self.symbols.entry(symbol).or_insert(state);
+        }
+    }
+
+    /// Zone of the `symbol` accepted from the zone detection.
+    pub fn detected(symbol: &str, zone: &Zone) -> Self {
+        Self {
+            symbol: Some(symbol.to_owned()),
+            priority: zone.priority.into(),
+            high: PriceLevelData(zone.high),
+            low: PriceLevelData(zone.low),
+            note: zone.note.clone(),
+            tags: zone.tags.clone(),
+            rules: zone.rules.clone(),
+            lifecycle: LifecycleData::from(zone),
+            symbols: BTreeMap::new(),
+        }
+    }
+}
+
+impl fmt::Display for ZoneData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:?} {} - {}",
+            self.symbol.as_deref().unwrap_or("*"),
+            self.priority,
+            self.low.0,
+            self.high.0
+        )
+    }
 }
 
 impl From<ZoneData> for Zone {
     fn from(data: ZoneData) -> Self {
+        let lifecycle = data.lifecycle;
         Self {
-            priority: data.priority.into(),
-            high: data.high.into(),
-            low: data.low.into(),
+            role: lifecycle.role,
+            state: lifecycle.state,
+            created: lifecycle.created,
+            expires: lifecycle.expires,
+            touches: lifecycle.touches,
+            breaks: lifecycle.breaks,
+            note: data.note,
+            tags: data.tags,
+            rules: data.rules,
+            ..Self::new(data.priority.into(), data.low.into(), data.high.into())
         }
     }
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-struct PriceLevelData(f64);
+#[derive(
+    Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct PriceLevelData(pub Price);
 
-impl From<PriceLevelData> for PriceLevel {
+impl From<PriceLevelData> for Price {
     fn from(data: PriceLevelData) -> Self {
-        Self(data.0)
+        data.0
     }
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-enum PriorityData {
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub enum PriorityData {
     High = 1,
     Medium = 2,
     Low = 3,
 }
 
+impl From<ZonePriority> for PriorityData {
+    fn from(priority: ZonePriority) -> Self {
+        match priority {
+            ZonePriority::High => PriorityData::High,
+            ZonePriority::Medium => PriorityData::Medium,
+            ZonePriority::Low => PriorityData::Low,
+        }
+    }
+}
+
 impl From<PriorityData> for ZonePriority {
     fn from(data: PriorityData) -> Self {
         match data {
@@ -69,8 +367,165 @@ impl From<PriorityData> for ZonePriority {
     }
 }
 
-pub fn load_save() -> io::Result<SaveData> {
-    let path = &format!(
+/// Alert as it is stored in the alert log.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AlertRecord {
+    /// Unix time in seconds.
+    pub time: u64,
+    pub symbol: String,
+    pub price: PriceLevelData,
+    pub position: Position,
+    pub cause: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone: Option<AlertZoneData>,
+    /// `None` if the bot stopped before the outcome was known.
+    pub outcome: Option<Outcome>,
+    pub best_move: f64,
+    pub worst_move: f64,
+}
+
+impl AlertRecord {
+    pub fn new(alert: &Alert, symbol: &str, time: u64) -> Self {
+        Self {
+            time,
+            symbol: symbol.to_owned(),
+            price: PriceLevelData(
+                Price::from_f64(alert.price().0).unwrap_or_default(),
+            ),
+            position: alert.position(),
+            cause: alert.cause().to_owned(),
+            zone: alert.zone().map(AlertZoneData::from),
+            outcome: alert.outcome(),
+            best_move: alert.best_move(),
+            worst_move: alert.worst_move(),
+        }
+    }
+
+    /// Alert of the record for the alert history. The `now` and the
+    /// `unix_time` are the same moment, the alert happened as long before
+    /// the `now` as the record's time is before the `unix_time`.
+    pub fn to_alert(&self, now: Instant, unix_time: u64) -> Alert {
+        let age = Duration::from_secs(unix_time.saturating_sub(self.time));
+        let alert = Alert::new(
+            now.checked_sub(age).unwrap_or(now),
+            self.price.0.level(),
+            self.position,
+            self.cause.clone(),
+        )
+        .with_outcome(self.outcome, self.best_move, self.worst_move);
+        match &self.zone {
+            Some(zone) => alert.with_zone(AlertZone::from(zone)),
+            None => alert,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AlertZoneData {
+    pub priority: PriorityData,
+    pub high: PriceLevelData,
+    pub low: PriceLevelData,
+}
+
+impl From<&AlertZoneData> for AlertZone {
+    fn from(data: &AlertZoneData) -> Self {
+        Self {
+            priority: data.priority.into(),
+            high: data.high.into(),
+            low: data.low.into(),
+        }
+    }
+}
+
+impl From<AlertZone> for AlertZoneData {
+    fn from(zone: AlertZone) -> Self {
+        Self {
+            priority: zone.priority.into(),
+            high: PriceLevelData(zone.high),
+            low: PriceLevelData(zone.low),
+        }
+    }
+}
+
+/// Appends the alerts to the alert log, one JSON record per line.
+#[derive(Debug)]
+pub struct AlertLog {
+    file: fs::File,
+}
+
+impl AlertLog {
+    pub fn open() -> io::Result<Self> {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(data_path(ALERT_LOG)?)?;
+        Ok(Self { file })
+    }
+
+    pub fn append(&mut self, record: &AlertRecord) -> io::Result<()> {
+        let line = serde_json::to_string(record).unwrap();
+        writeln!(self.file, "{line}")
+    }
+}
+
+/// Appends the orders to the order log, one JSON record per line.
+/// Orders of the dry-run mode are logged too.
+#[derive(Debug)]
+pub struct OrderLog {
+    file: fs::File,
+}
+
+impl OrderLog {
+    pub fn open() -> io::Result<Self> {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(data_path(ORDER_LOG)?)?;
+        Ok(Self { file })
+    }
+
+    pub fn append(&mut self, record: &OrderRecord) -> io::Result<()> {
+        let line = serde_json::to_string(record).unwrap();
+        writeln!(self.file, "{line}")
+    }
+}
+
+pub fn load_alert_log() -> io::Result<Vec<AlertRecord>> {
+    let content = match fs::read_to_string(data_path(ALERT_LOG)?) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+        .collect()
+}
+
+/// Reads the exchange info cached by [`save_symbol_cache`].
+pub fn load_symbol_cache() -> io::Result<SymbolCatalog> {
+    let content = fs::read(data_path(SYMBOL_CACHE)?)?;
+    serde_json::from_slice(&content)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn save_symbol_cache(catalog: &SymbolCatalog) -> io::Result<()> {
+    let serialized = serde_json::to_string(catalog).unwrap();
+    fs::write(data_path(SYMBOL_CACHE)?, serialized)
+}
+
+/// Config file read when no other file is given with `--config`.
+pub fn config_path() -> io::Result<String> {
+    data_path(CONFIG)
+}
+
+/// Data files are kept next to the executable.
+fn data_path(file: &str) -> io::Result<String> {
+    Ok(format!(
         "{}/{}",
         std::env::current_exe()?
             .parent()
@@ -78,51 +533,152 @@ pub fn load_save() -> io::Result<SaveData> {
             .to_str()
             .unwrap()
             .trim_start_matches("\\\\?\\"),
-        SAVE
-    );
+        file
+    ))
+}
+
+fn save_path() -> io::Result<String> {
+    data_path(SAVE)
+}
+
+pub fn load_save() -> io::Result<SaveData> {
+    let path = &save_path()?;
     match fs::read(path) {
-        Ok(f) => {
-            println!("Save file found!");
-            match serde_json::from_slice(&f) {
-                Ok(data) => Ok(data),
-                // TODO implement custom error types
-                Err(_) => Err(io::Error::from(io::ErrorKind::InvalidData)),
-            }
-        }
+        Ok(f) => match serde_json::from_slice(&f) {
+            Ok(data) => Ok(data),
+            // TODO implement custom error types
+            Err(_) => Err(io::Error::from(io::ErrorKind::InvalidData)),
+        },
         Err(e) => Err(e),
     }
 }
 
-pub fn save_data(data: &SaveData) -> io::Result<()> {
-    let path = &format!(
-        "{}/{}",
-        std::env::current_exe()?.parent().unwrap().to_str().unwrap(),
-        SAVE
-    );
+/// Writes the `data` to the save file. Returns the message for the user
+/// if the save file went missing and was created again.
+pub fn save_data(data: &SaveData) -> io::Result<Option<String>> {
+    let path = &save_path()?;
     let serialized = serde_json::to_string(data).unwrap();
 
     // Error check in case the file is missing
-    if let Err(e) = fs::File::open(path) {
-        match e.kind() {
-            io::ErrorKind::NotFound => println!(
-                "The save file is missing! \
-        Creating a new one with memorized data."
-            ),
-            _ => return Err(e),
+    let status = match fs::File::open(path) {
+        Ok(_) => None,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            Some(String::from("The save file was missing, created a new one"))
         }
-    }
+        Err(e) => return Err(e),
+    };
 
-    fs::write(SAVE, serialized)
+    fs::write(path, serialized)?;
+    Ok(status)
 }
 
 pub fn new_save() -> io::Result<SaveData> {
-    println!("Creating a new save file...");
-    let path = &format!(
-        "{}/{}",
-        std::env::current_exe()?.parent().unwrap().to_str().unwrap(),
-        SAVE
-    );
+    let path = &save_path()?;
     let form = serde_json::to_string(&SaveData::empty()).unwrap();
     fs::write(path, form)?;
     Ok(SaveData::empty())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{PriorityData, SaveData};
+    use crate::{
+        price::Price,
+        strategy::{Zone, ZonePriority, ZoneState},
+    };
+
+    fn save(zones: &str) -> SaveData {
+        serde_json::from_str(&format!(r#"{{"zones": [{zones}]}}"#)).unwrap()
+    }
+
+    #[test]
+    fn validation_fixes_and_removes_zones() {
+        let mut data = save(
+            r#"{"priority": "High", "high": 1200.0, "low": 1250.0},
+               {"priority": "Low", "high": 1250.0, "low": 1200.0},
+               {"priority": "Low", "high": 10.0, "low": -5.0},
+               {"priority": "Low", "high": "1260.25", "low": "1240"}"#,
+        );
+        let issues = data.validate();
+
+        assert_eq!(issues.len(), 4);
+        assert!(issues[0].starts_with("Inverted zone flipped"));
+        assert!(issues[1].starts_with("Duplicate zone removed"));
+        assert!(issues[2].starts_with("Zone removed, bounds are not positive"));
+        assert!(issues[3].contains("overlaps"));
+        assert_eq!(data.zones().len(), 2);
+        assert_eq!(data.zones()[0].low.0, Price::new(1200, 0));
+        assert_eq!(data.zones()[1].high.0, Price::new(126025, 2));
+    }
+
+    #[test]
+    fn global_zones_keep_their_lifecycle_per_symbol() {
+        let mut data = save(
+            r#"{"priority": "High", "high": 1250.0, "low": 1200.0},
+               {"symbol": "BTCUSDT", "priority": "Low",
+                "high": 60000.0, "low": 59000.0},
+               {"symbol": "ETHUSDT", "priority": "Low",
+                "high": 1100.0, "low": 1050.0}"#,
+        );
+
+        let mut eth = data.data("ETHUSDT");
+        assert_eq!(eth.len(), 2);
+        eth[0].breaks = 3;
+        eth[0].state = ZoneState::Invalidated;
+        eth[0].high = Price::new(12505, 1);
+        eth[1].touches = 2;
+        data.sync_zones("ETHUSDT", &eth);
+
+        let btc = data.data("BTCUSDT");
+        assert_eq!((btc[0].breaks, btc[0].state), (0, ZoneState::Active));
+        assert_eq!(btc[0].high, Price::new(1250, 0));
+        assert_eq!(btc[1].low, Price::new(59000, 0));
+
+        let eth = data.data("ETHUSDT");
+        assert_eq!((eth[0].breaks, eth[0].state), (3, ZoneState::Invalidated));
+        assert_eq!(eth[0].high, Price::new(12505, 1));
+        assert_eq!(eth[1].touches, 2);
+        // The global zone itself is not changed.
+        assert_eq!(data.zones()[0].lifecycle.breaks, 0);
+        assert_eq!(data.zones()[2].lifecycle.touches, 2);
+
+        // Zones which are not saved or belong to another symbol are skipped.
+        let mut btc = data.data("BTCUSDT");
+        btc.reverse();
+        btc.push(Zone::new(
+            ZonePriority::High,
+            Price::new(1, 0),
+            Price::new(2, 0),
+        ));
+        btc[0].touches = 7;
+        data.sync_zones("BTCUSDT", &btc);
+        assert_eq!(data.zones().len(), 3);
+        assert_eq!(data.zones()[1].lifecycle.touches, 7);
+        assert_eq!(data.data("BTCUSDT")[1].touches, 7);
+    }
+
+    #[test]
+    fn overlapping_zones_of_same_priority_merge() {
+        let mut data = save(
+            r#"{"priority": "High", "high": 1250.0, "low": 1200.0,
+                "note": "weekly open", "touches": 2},
+               {"priority": "Low", "high": 1300.0, "low": 1240.0},
+               {"priority": "High", "high": 1330.0, "low": 1290.0},
+               {"priority": "High", "high": 1295.0, "low": 1245.0,
+                "note": "FOMC wick", "touches": 3},
+               {"symbol": "BTCUSDT", "priority": "High",
+                "high": 1250.0, "low": 1200.0}"#,
+        );
+
+        assert_eq!(data.merge_overlapping(), 2);
+        let zones = data.zones();
+        assert_eq!(zones.len(), 3);
+        assert_eq!(zones[0].priority, PriorityData::High);
+        assert_eq!(
+            (zones[0].low.0, zones[0].high.0),
+            (Price::new(1200, 0), Price::new(1330, 0))
+        );
+        assert_eq!(zones[0].note.as_deref(), Some("weekly open; FOMC wick"));
+        assert_eq!(zones[0].lifecycle.touches, 5);
+    }
+}
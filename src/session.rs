@@ -0,0 +1,184 @@
+use std::{
+    fs,
+    io::{self, BufWriter, Write},
+    path::Path,
+    sync::{mpsc::Sender, Arc},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    clock::Clock,
+    feed::{MarketEvent, Ticker},
+    price::Price,
+    symbol::Symbol,
+    venue::VenueKind,
+};
+
+/// Single price update received by the bot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedTick {
+    /// Time since the start of the recording.
+    pub time: Duration,
+    pub symbol: Symbol,
+    pub price: Price,
+}
+
+/// Writes every price update the bot receives to a file, one update per
+/// line in the `{millis} {symbol} {price}` format.
+#[derive(Debug)]
+pub struct SessionRecorder {
+    writer: BufWriter<fs::File>,
+    start: Instant,
+}
+
+impl SessionRecorder {
+    pub fn create<P: AsRef<Path>>(path: P, start: Instant) -> io::Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(fs::File::create(path)?),
+            start,
+        })
+    }
+
+    pub fn record(
+        &mut self,
+        now: Instant,
+        symbol: &Symbol,
+        price: Price,
+    ) -> io::Result<()> {
+        let millis = now.saturating_duration_since(self.start).as_millis();
+        writeln!(self.writer, "{} {} {}", millis, symbol, price)?;
+        // Flush right away so the session survives a crash.
+        self.writer.flush()
+    }
+}
+
+/// Reads the recorded session from the file.
+pub fn load_session<P: AsRef<Path>>(path: P) -> io::Result<Vec<RecordedTick>> {
+    let invalid = |line: usize| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid session record at line {}", line + 1),
+        )
+    };
+
+    let content = fs::read_to_string(path)?;
+    let mut ticks = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let (Some(millis), Some(symbol), Some(price), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid(i));
+        };
+        ticks.push(RecordedTick {
+            time: Duration::from_millis(
+                millis.parse().map_err(|_| invalid(i))?,
+            ),
+            symbol: Symbol::new(symbol),
+            price: price.parse().map_err(|_| invalid(i))?,
+        });
+    }
+    Ok(ticks)
+}
+
+/// Sends the recorded ticks to the bot as the prices of the `venue` with
+/// the same timing as they were recorded. How fast the session replays
+/// depends on the provided `clock`.
+///
+/// Works the same way as the live market feed so the bot can not
+/// tell the difference between the replay and the live market.
+pub fn spawn_replay(
+    ticks: Vec<RecordedTick>,
+    venue: VenueKind,
+    clock: Arc<dyn Clock>,
+    tx: Sender<MarketEvent>,
+) {
+    thread::spawn(move || {
+        let mut last = Duration::ZERO;
+        for tick in ticks {
+            clock.sleep(tick.time.saturating_sub(last));
+            last = tick.time;
+
+            let price = Ticker {
+                symbol: tick.symbol,
+                price: tick.price,
+            };
+            if tx.send(MarketEvent::Price(venue, Ok(price))).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{mpsc::channel, Arc},
+        thread,
+        time::Duration,
+    };
+
+    use super::{load_session, spawn_replay, SessionRecorder};
+    use crate::{
+        clock::{Clock, ManualClock},
+        feed::MarketEvent,
+        price::Price,
+        symbol::Symbol,
+        venue::VenueKind,
+    };
+
+    #[test]
+    fn recorded_session_loads_back_exactly() {
+        let path = std::env::temp_dir()
+            .join(format!("zone-bot-session-test-{}.txt", std::process::id()));
+        let clock = ManualClock::new();
+        let mut recorder = SessionRecorder::create(&path, clock.now()).unwrap();
+
+        let prices = [
+            Price::new(123456, 2),
+            Price::new(1234, 8),
+            Price::new(654321, 1),
+        ];
+        for price in prices {
+            clock.advance(Duration::from_millis(2000));
+            recorder
+                .record(clock.now(), &Symbol::new("ETHUSDT"), price)
+                .unwrap();
+        }
+        let ticks = load_session(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let loaded: Vec<Price> = ticks.iter().map(|t| t.price).collect();
+        assert_eq!(loaded, prices);
+        assert_eq!(ticks[2].time, Duration::from_millis(6000));
+        assert_eq!(ticks[0].symbol, Symbol::new("ETHUSDT"));
+    }
+
+    #[test]
+    fn replay_follows_recorded_timing() {
+        let path = std::env::temp_dir()
+            .join(format!("zone-bot-replay-test-{}.txt", std::process::id()));
+        std::fs::write(&path, "0 ETHUSDT 100\n2000 ETHUSDT 101\n").unwrap();
+        let ticks = load_session(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let clock = Arc::new(ManualClock::new());
+        let (tx, reader) = channel();
+        spawn_replay(ticks, VenueKind::Bybit, clock.clone(), tx);
+        let price = || match reader.recv().unwrap() {
+            MarketEvent::Price(VenueKind::Bybit, price) => price.unwrap().price,
+            event => panic!("{event:?} is not a Bybit price"),
+        };
+
+        assert_eq!(price(), Price::new(100, 0));
+        // The second tick waits for 2 seconds of simulated time.
+        thread::sleep(Duration::from_millis(50));
+        assert!(reader.try_recv().is_err());
+        clock.advance(Duration::from_millis(2000));
+        assert_eq!(price(), Price::new(101, 0));
+    }
+}
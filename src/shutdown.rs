@@ -0,0 +1,51 @@
+use std::{
+    io, panic,
+    sync::{atomic::AtomicBool, Arc},
+};
+
+use crossterm::{
+    cursor, execute,
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+/// Switches the terminal to the raw mode and the alternate screen
+/// the console draws to.
+pub fn enter_terminal() -> io::Result<()> {
+    terminal::enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)
+}
+
+/// Puts the terminal back into the state it was in before
+/// [`enter_terminal`]. Can be called more than once.
+pub fn restore_terminal() {
+    // Restore as much as possible, there is nobody to report the errors to.
+    let _ = terminal::disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, cursor::Show);
+}
+
+/// Restores the terminal before the panic message is printed so the message
+/// is readable and the shell is usable after the crash.
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        default_hook(info);
+    }));
+}
+
+/// Returns the flag which is set once the process receives SIGINT or
+/// SIGTERM so the main loop can stop and save the state. The second
+/// signal terminates the process right away.
+pub fn stop_signal() -> io::Result<Arc<AtomicBool>> {
+    let stop = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    for signal in [signal_hook::consts::SIGINT, signal_hook::consts::SIGTERM] {
+        signal_hook::flag::register_conditional_shutdown(
+            signal,
+            1,
+            stop.clone(),
+        )?;
+        signal_hook::flag::register(signal, stop.clone())?;
+    }
+    Ok(stop)
+}
@@ -1,37 +1,936 @@
-use crate::bot::PriceLevel;
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    alert::{Alert, AlertRules, AlertSink, AlertTrigger, AlertZone, Position},
+    bot::PriceLevel,
+    index::ZoneIndex,
+    price::Price,
+};
 
 pub struct ZoneStrat {
     zones: Vec<Zone>,
-    closest_upper: PriceLevel,
-    closest_bottom: PriceLevel,
+    /// Bounds of the active zones by their position in `zones`.
+    index: ZoneIndex,
+    /// Index of the nearest zone above the price.
+    closest_upper: Option<usize>,
+    /// Index of the nearest zone below the price.
+    closest_bottom: Option<usize>,
+    /// Price from the previous analysis.
+    last_price: PriceLevel,
+    /// Zone is invalidated after the price breaks through it this many times.
+    max_breaks: u32,
+    /// How close the price has to come to a zone to raise an alert.
+    proximity: Option<Proximity>,
+    /// Zones the price is currently close to.
+    near_zones: Vec<usize>,
+    /// How the zones without their own rules alert.
+    defaults: AlertDefaults,
+    atr: Atr,
+    /// Whether the zones changed since the last [`ZoneStrat::take_changed`].
+    changed: bool,
 }
 
 impl ZoneStrat {
+    /// Default minimum time between two alerts of the same zone.
+    pub const ALERT_COOLDOWN: Duration = Duration::from_secs(5 * 60);
+    pub const DEFAULT_MAX_BREAKS: u32 = 3;
+
     pub fn from_zones(zones: Vec<Zone>) -> Self {
-        Self {
+        let mut strat = Self {
             zones,
-            closest_bottom: PriceLevel::NAN,
-            closest_upper: PriceLevel::NAN,
+            index: ZoneIndex::new(),
+            closest_bottom: None,
+            closest_upper: None,
+            last_price: PriceLevel::NAN,
+            max_breaks: Self::DEFAULT_MAX_BREAKS,
+            proximity: None,
+            near_zones: Vec::new(),
+            defaults: AlertDefaults::default(),
+            atr: Atr::default(),
+            changed: false,
+        };
+        strat.index_bounds();
+        strat
+    }
+
+    /// Replaces the zones, e.g. with the zones of another symbol,
+    /// and keeps the settings.
+    pub fn replace_zones(&mut self, zones: Vec<Zone>) {
+        *self = Self {
+            max_breaks: self.max_breaks,
+            proximity: self.proximity,
+            defaults: self.defaults.clone(),
+            ..Self::from_zones(zones)
+        };
+    }
+
+    pub fn set_alert_defaults(&mut self, defaults: AlertDefaults) {
+        self.defaults = defaults;
+    }
+
+    pub fn alert_defaults(&self) -> &AlertDefaults {
+        &self.defaults
+    }
+
+    pub fn set_max_breaks(&mut self, max_breaks: u32) {
+        self.max_breaks = max_breaks;
+    }
+
+    pub fn set_proximity(&mut self, proximity: Proximity) {
+        self.proximity = Some(proximity);
+    }
+
+    /// Expires the zones past their expiry and flips the role of the zones
+    /// the `price` closed through. The price of every analysis counts as
+    /// a close. Zones broken [`ZoneStrat::set_max_breaks`] times are
+    /// invalidated. Expired and invalidated zones don't alert anymore.
+    /// Returns the alerts of the broken zones.
+    pub fn update_lifecycle(
+        &mut self,
+        price: PriceLevel,
+        unix_time: u64,
+        now: Instant,
+    ) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        for (i, zone) in self.zones.iter_mut().enumerate() {
+            if !zone.is_active() {
+                continue;
+            }
+            if zone.expires.is_some_and(|expires| unix_time >= expires) {
+                zone.state = ZoneState::Expired;
+                self.index.remove(i);
+                self.changed = true;
+                continue;
+            }
+
+            // Inside of the zone or NAN
+            let Some(side) = zone.side(price) else {
+                continue;
+            };
+            match zone.role {
+                Some(role) if role == side => continue,
+                Some(_) => {
+                    zone.breaks += 1;
+                    if zone.breaks >= self.max_breaks {
+                        zone.state = ZoneState::Invalidated;
+                        self.index.remove(i);
+                    }
+                    alerts.extend(zone.alert(
+                        AlertTrigger::Break,
+                        side,
+                        price,
+                        now,
+                        &self.defaults,
+                    ));
+                }
+                None => (),
+            }
+            zone.role = Some(side);
+            self.changed = true;
+        }
+        alerts
+    }
+
+    /// Returns whether the zones changed since the last call
+    /// and should be saved.
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// Tracks the range of the live price between two analyses
+    /// for the [`Proximity::Atr`] distance.
+    pub fn track_price(&mut self, price: PriceLevel) {
+        self.atr.track(price);
+    }
+
+    /// Finds the nearest active zones above and below the `price`.
+    /// The zone above is the one with the lowest `low` above the price
+    /// and the zone below is the one with the highest `high` below it.
+    pub fn update_closest(&mut self, price: PriceLevel) {
+        self.closest_upper = None;
+        self.closest_bottom = None;
+        if price.0.is_nan() {
+            return;
+        }
+
+        self.closest_upper = self.index.nearest_above(price.0).map(|(_, i)| i);
+        self.closest_bottom = self.index.nearest_below(price.0).map(|(_, i)| i);
+    }
+
+    /// Indexes the bounds of the active zones.
+    fn index_bounds(&mut self) {
+        let active =
+            self.zones.iter().enumerate().filter(|(_, z)| z.is_active());
+        self.index = ZoneIndex::from_zones(
+            active.map(|(i, z)| (i, z.low_f64(), z.high_f64())),
+        );
+    }
+
+    pub fn zones(&self) -> &[Zone] {
+        &self.zones
+    }
+
+    pub fn add_zone(&mut self, zone: Zone) {
+        if zone.is_active() {
+            self.index.insert(
+                self.zones.len(),
+                zone.low_f64(),
+                zone.high_f64(),
+            );
+        }
+        self.zones.push(zone);
+    }
+
+    /// Moves the zone at `index` to the new bounds. Fails if the bounds are
+    /// invalid or the same as the bounds of another zone.
+    pub fn edit_zone(
+        &mut self,
+        index: usize,
+        low: Price,
+        high: Price,
+    ) -> Result<(), &'static str> {
+        check_bounds(low, high)?;
+        let duplicate = self
+            .zones
+            .iter()
+            .enumerate()
+            .any(|(i, z)| i != index && z.low == low && z.high == high);
+        if duplicate {
+            return Err("another zone has the same bounds");
+        }
+
+        let zone = &mut self.zones[index];
+        zone.low = low;
+        zone.high = high;
+        if zone.is_active() {
+            self.index.insert(index, low.to_f64(), high.to_f64());
         }
+        Ok(())
     }
+
+    /// `low` of the nearest zone above the price
+    /// or [`PriceLevel::NAN`] if there is no such zone.
+    pub fn closest_upper(&self) -> PriceLevel {
+        self.closest_upper
+            .map_or(PriceLevel::NAN, |i| self.zones[i].low.level())
+    }
+
+    /// `high` of the nearest zone below the price
+    /// or [`PriceLevel::NAN`] if there is no such zone.
+    pub fn closest_bottom(&self) -> PriceLevel {
+        self.closest_bottom
+            .map_or(PriceLevel::NAN, |i| self.zones[i].high.level())
+    }
+
+    /// Nearest side of the next zone beyond the `low` - `high` zone in the
+    /// direction of the `position` or [`PriceLevel::NAN`] if there is none.
+    pub fn next_zone(
+        &self,
+        low: Price,
+        high: Price,
+        position: Position,
+    ) -> PriceLevel {
+        let next = match position {
+            Position::Buy => self.index.nearest_above(high.to_f64()),
+            Position::Sell => self.index.nearest_below(low.to_f64()),
+        };
+        next.map_or(PriceLevel::NAN, |(price, _)| PriceLevel(price))
+    }
+
+    /// Alerts when the `price` comes within the proximity distance of the
+    /// nearest zone above or below. The zone alerts again only after the
+    /// price moves away from it.
+    fn approach(&mut self, price: PriceLevel, now: Instant) -> Vec<Alert> {
+        let Some(distance) =
+            self.proximity.and_then(|p| p.distance(price, &self.atr))
+        else {
+            self.near_zones.clear();
+            return Vec::new();
+        };
+        self.update_closest(price);
+
+        let mut alerts = Vec::new();
+        let mut near_zones = Vec::new();
+        let candidates = [
+            (self.closest_upper, ZoneRole::Resistance),
+            (self.closest_bottom, ZoneRole::Support),
+        ];
+        for (index, side) in candidates {
+            let Some(i) = index else {
+                continue;
+            };
+            let zone = &mut self.zones[i];
+            let gap = match side {
+                ZoneRole::Resistance => zone.low_f64() - price.0,
+                ZoneRole::Support => price.0 - zone.high_f64(),
+            };
+            if gap > distance {
+                continue;
+            }
+            near_zones.push(i);
+            if !self.near_zones.contains(&i) {
+                alerts.extend(zone.alert(
+                    AlertTrigger::Approach,
+                    side,
+                    price,
+                    now,
+                    &self.defaults,
+                ));
+            }
+        }
+        self.near_zones = near_zones;
+        alerts
+    }
+}
+
+impl Strategy for ZoneStrat {
+    /// Alerts when the price enters a zone, gets rejected from it
+    /// or approaches it closer than the [`Proximity`] distance.
+    /// Zones alerting at once are combined into a single alert.
+    /// Entering from above suggests buying at the support and entering
+    /// from below suggests selling at the resistance. Which of these alert
+    /// depends on the zone's [`AlertRules`]. Each zone alerts at most once
+    /// per [`AlertDefaults::cooldown`] and only while it is active.
+    fn analyze(&mut self, price: PriceLevel, now: Instant) -> Vec<Alert> {
+        let last = self.last_price;
+        self.last_price = price;
+        self.atr.close(price);
+        if price.0.is_nan() || last.0.is_nan() {
+            return Vec::new();
+        }
+
+        // Only the zones containing either of the prices can be entered
+        // or left.
+        let mut candidates = self.index.containing(price.0);
+        candidates.extend(self.index.containing(last.0));
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let mut alerts = Vec::new();
+        for i in candidates {
+            let zone = &mut self.zones[i];
+            let (trigger, side) = match (zone.side(last), zone.side(price)) {
+                (Some(from), None) => {
+                    zone.touches += 1;
+                    zone.entered_from = Some(from);
+                    self.changed = true;
+                    let trigger = match from {
+                        ZoneRole::Support => AlertTrigger::FromAbove,
+                        ZoneRole::Resistance => AlertTrigger::FromBelow,
+                    };
+                    (trigger, from)
+                }
+                (None, Some(to)) => {
+                    if zone.entered_from.take() != Some(to) {
+                        continue;
+                    }
+                    (AlertTrigger::Reject, to)
+                }
+                _ => continue,
+            };
+            alerts.extend(zone.alert(
+                trigger,
+                side,
+                price,
+                now,
+                &self.defaults,
+            ));
+        }
+        alerts.extend(self.approach(price, now));
+        Alert::combine(alerts).into_iter().collect()
+    }
+}
+
+/// How the zones alert unless their own [`AlertRules`] say otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertDefaults {
+    /// Minimum time between two alerts of the same zone.
+    pub cooldown: Duration,
+    pub sinks: Vec<AlertSink>,
 }
 
-impl Strategy for ZoneStrat {}
+impl Default for AlertDefaults {
+    fn default() -> Self {
+        Self {
+            cooldown: ZoneStrat::ALERT_COOLDOWN,
+            sinks: AlertRules::DEFAULT_SINKS.to_vec(),
+        }
+    }
+}
 
 /// Represents a "resistance" or a "support" zone with the `high` and the `low` limit.
 /// Priority represents the credibility of each zone.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Zone {
     pub priority: ZonePriority,
-    pub high: PriceLevel,
-    pub low: PriceLevel,
+    pub high: Price,
+    pub low: Price,
+    /// `None` until the price is seen outside of the zone.
+    pub role: Option<ZoneRole>,
+    pub state: ZoneState,
+    /// Unix time in seconds, `None` for zones saved before it was tracked.
+    pub created: Option<u64>,
+    /// Unix time in seconds after which the zone stops alerting.
+    pub expires: Option<u64>,
+    /// How many times the price entered the zone.
+    pub touches: u32,
+    /// How many times the price closed through the zone.
+    pub breaks: u32,
+    /// Reasoning behind the zone, e.g. "weekly open".
+    pub note: Option<String>,
+    pub tags: Vec<String>,
+    pub rules: AlertRules,
+    /// Side the price entered the zone from.
+    pub entered_from: Option<ZoneRole>,
+    /// When the zone last raised an alert.
+    pub last_alert: Option<Instant>,
+    /// When the zone last raised an approach alert. Approaching has its own
+    /// cooldown so it doesn't silence the alert of entering the zone.
+    pub last_approach: Option<Instant>,
+    /// Index of the saved zone this zone was loaded from, `None` if it is
+    /// not saved.
+    pub saved: Option<usize>,
 }
 
-#[derive(Debug, Clone, Copy)]
+impl Zone {
+    pub fn new(priority: ZonePriority, low: Price, high: Price) -> Self {
+        Self {
+            priority,
+            high,
+            low,
+            role: None,
+            state: ZoneState::Active,
+            created: None,
+            expires: None,
+            touches: 0,
+            breaks: 0,
+            note: None,
+            tags: Vec::new(),
+            rules: AlertRules::default(),
+            entered_from: None,
+            last_alert: None,
+            last_approach: None,
+            saved: None,
+        }
+    }
+
+    /// Role the zone has for the `price` outside of it,
+    /// `None` if the `price` is inside of the zone.
+    fn side(&self, price: PriceLevel) -> Option<ZoneRole> {
+        if price.0.is_nan() || self.contains(price) {
+            None
+        } else if price.0 > self.high_f64() {
+            Some(ZoneRole::Support)
+        } else {
+            Some(ZoneRole::Resistance)
+        }
+    }
+
+    /// Creates the alert if the zone's rules allow the `trigger` and the
+    /// zone is not cooling down. The price on the `side` of a support
+    /// suggests buying and on the `side` of a resistance selling.
+    /// The zone's rules without the sinks deliver to the `defaults` sinks.
+    fn alert(
+        &mut self,
+        trigger: AlertTrigger,
+        side: ZoneRole,
+        price: PriceLevel,
+        now: Instant,
+        defaults: &AlertDefaults,
+    ) -> Option<Alert> {
+        if !self.rules.triggers().contains(&trigger) {
+            return None;
+        }
+        let last_alert = match trigger {
+            AlertTrigger::Approach => &mut self.last_approach,
+            _ => &mut self.last_alert,
+        };
+        let cooling_down = last_alert.is_some_and(|t| {
+            now.saturating_duration_since(t) < defaults.cooldown
+        });
+        if cooling_down {
+            return None;
+        }
+        *last_alert = Some(now);
+
+        let position = match side {
+            ZoneRole::Support => Position::Buy,
+            ZoneRole::Resistance => Position::Sell,
+        };
+        let zone = AlertZone {
+            priority: self.priority,
+            low: self.low,
+            high: self.high,
+        };
+        Some(
+            Alert::new(now, price, position, self.message(trigger, price))
+                .with_zone(zone)
+                .with_sinks(
+                    self.rules.sinks.as_deref().unwrap_or(&defaults.sinks),
+                ),
+        )
+    }
+
+    /// Fills in the zone's message template. Without the template
+    /// the message describes the event followed by the note.
+    fn message(&self, trigger: AlertTrigger, price: PriceLevel) -> String {
+        let Some(template) = &self.rules.message else {
+            let mut message = format!(
+                "Price {} {:?} zone {} - {}",
+                trigger.event(),
+                self.priority,
+                self.low,
+                self.high
+            );
+            if let Some(note) = &self.note {
+                message.push_str(": ");
+                message.push_str(note);
+            }
+            return message;
+        };
+        template
+            .replace("{event}", trigger.event())
+            .replace("{price}", &price.0.to_string())
+            .replace("{priority}", &format!("{:?}", self.priority))
+            .replace("{low}", &self.low.to_string())
+            .replace("{high}", &self.high.to_string())
+            .replace("{note}", self.note.as_deref().unwrap_or(""))
+            .replace("{tags}", &self.tags.join(", "))
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.state == ZoneState::Active
+    }
+
+    /// Floating `low` for the calculations with the live price.
+    pub fn low_f64(&self) -> f64 {
+        self.low.to_f64()
+    }
+
+    /// Floating `high` for the calculations with the live price.
+    pub fn high_f64(&self) -> f64 {
+        self.high.to_f64()
+    }
+
+    pub fn contains(&self, price: PriceLevel) -> bool {
+        price.0 >= self.low_f64() && price.0 <= self.high_f64()
+    }
+
+    pub fn overlaps(&self, other: &Zone) -> bool {
+        self.low <= other.high && other.low <= self.high
+    }
+}
+
+/// Higher priorities sort first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ZonePriority {
     High,
     Medium,
     Low,
 }
 
-pub trait Strategy {}
+/// Checks that the zone bounds are positive prices.
+pub fn check_bounds(low: Price, high: Price) -> Result<(), &'static str> {
+    if !low.is_positive() {
+        Err("bounds are not positive")
+    } else if low > high {
+        Err("low is above high")
+    } else {
+        Ok(())
+    }
+}
+
+/// Price above the zone makes it a support and price below it
+/// makes it a resistance.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub enum ZoneRole {
+    Support,
+    Resistance,
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum ZoneState {
+    #[default]
+    Active,
+    Expired,
+    Invalidated,
+}
+
+/// Distance from a zone boundary at which the approach alert fires.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Proximity {
+    /// Distance in the quote currency.
+    Absolute(f64),
+    /// Distance in percent of the price.
+    Percent(f64),
+    /// Multiple of the average true range.
+    Atr(f64),
+}
+
+impl Proximity {
+    /// Distance for the current `price`. `None` while there
+    /// isn't enough price history for the ATR.
+    fn distance(self, price: PriceLevel, atr: &Atr) -> Option<f64> {
+        match self {
+            Proximity::Absolute(distance) => Some(distance),
+            Proximity::Percent(percent) => Some(price.0 * percent / 100.0),
+            Proximity::Atr(multiple) => atr.value.map(|atr| atr * multiple),
+        }
+    }
+}
+
+impl FromStr for Proximity {
+    type Err = String;
+
+    /// Parses `25` as an absolute distance, `0.5%` as a percent
+    /// and `1.5atr` as a multiple of the ATR.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        let (value, kind): (&str, fn(f64) -> Self) =
+            if let Some(percent) = s.strip_suffix('%') {
+                (percent, Proximity::Percent)
+            } else if let Some(multiple) = s.strip_suffix("atr") {
+                (multiple, Proximity::Atr)
+            } else {
+                (&s, Proximity::Absolute)
+            };
+        match value.trim().parse::<f64>() {
+            Ok(value) if value > 0.0 && value.is_finite() => Ok(kind(value)),
+            _ => Err(format!("Invalid proximity: {s}")),
+        }
+    }
+}
+
+/// Average true range where every period between two analyses is a candle.
+#[derive(Debug)]
+struct Atr {
+    /// Range of the current candle, [`f64::NAN`] before the first price.
+    high: f64,
+    low: f64,
+    previous_close: Option<f64>,
+    /// True ranges of the first candles until there is enough of them.
+    first_ranges: Vec<f64>,
+    value: Option<f64>,
+}
+
+impl Default for Atr {
+    fn default() -> Self {
+        Self {
+            high: f64::NAN,
+            low: f64::NAN,
+            previous_close: None,
+            first_ranges: Vec::with_capacity(Self::PERIOD),
+            value: None,
+        }
+    }
+}
+
+impl Atr {
+    const PERIOD: usize = 14;
+
+    fn track(&mut self, price: PriceLevel) {
+        if price.0.is_nan() {
+            return;
+        }
+        self.high = self.high.max(price.0);
+        self.low = self.low.min(price.0);
+    }
+
+    /// Closes the current candle and smooths the ATR with its true range.
+    fn close(&mut self, price: PriceLevel) {
+        if price.0.is_nan() {
+            return;
+        }
+        self.track(price);
+        if let Some(previous) = self.previous_close {
+            let range = (self.high - self.low)
+                .max((self.high - previous).abs())
+                .max((self.low - previous).abs());
+            let period = Self::PERIOD as f64;
+            self.value = match self.value {
+                Some(atr) => Some((atr * (period - 1.0) + range) / period),
+                None => {
+                    self.first_ranges.push(range);
+                    (self.first_ranges.len() == Self::PERIOD)
+                        .then(|| self.first_ranges.iter().sum::<f64>() / period)
+                }
+            };
+        }
+        self.previous_close = Some(price.0);
+        self.high = price.0;
+        self.low = price.0;
+    }
+}
+
+pub trait Strategy {
+    /// Analyzes the latest `price` and returns the alerts it caused.
+    fn analyze(&mut self, price: PriceLevel, now: Instant) -> Vec<Alert>;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{
+        Proximity, Strategy, Zone, ZonePriority, ZoneRole, ZoneState, ZoneStrat,
+    };
+    use crate::{
+        alert::{AlertSink, AlertTrigger, Position},
+        bot::PriceLevel,
+        price::Price,
+    };
+
+    fn strat() -> ZoneStrat {
+        ZoneStrat::from_zones(vec![Zone::new(
+            ZonePriority::High,
+            Price::new(1000, 0),
+            Price::new(1100, 0),
+        )])
+    }
+
+    #[test]
+    fn entering_zone_from_above_suggests_buying() {
+        let mut strat = strat();
+        let now = Instant::now();
+
+        assert!(strat.analyze(PriceLevel(1200.0), now).is_empty());
+        let alerts = strat.analyze(PriceLevel(1050.0), now);
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].position(), Position::Buy);
+        // Staying inside of the zone does not alert again.
+        assert!(strat.analyze(PriceLevel(1040.0), now).is_empty());
+    }
+
+    #[test]
+    fn zone_alerts_again_only_after_cooldown() {
+        let mut strat = strat();
+        let start = Instant::now();
+        let enter = |strat: &mut ZoneStrat, now| {
+            strat.analyze(PriceLevel(900.0), now);
+            strat.analyze(PriceLevel(1050.0), now).len()
+        };
+
+        assert_eq!(enter(&mut strat, start), 1);
+        let almost = start + ZoneStrat::ALERT_COOLDOWN - Duration::from_secs(1);
+        assert_eq!(enter(&mut strat, almost), 0);
+        assert_eq!(enter(&mut strat, start + ZoneStrat::ALERT_COOLDOWN), 1);
+    }
+
+    #[test]
+    fn closing_through_zone_flips_and_invalidates_it() {
+        let mut strat = strat();
+        strat.set_max_breaks(2);
+        let role = |strat: &ZoneStrat| strat.zones()[0].role;
+
+        let now = Instant::now();
+
+        strat.update_lifecycle(PriceLevel(1200.0), 0, now);
+        assert_eq!(role(&strat), Some(ZoneRole::Support));
+        // Wicking into the zone is not a break.
+        strat.update_lifecycle(PriceLevel(1050.0), 0, now);
+        assert_eq!(role(&strat), Some(ZoneRole::Support));
+
+        strat.update_lifecycle(PriceLevel(950.0), 0, now);
+        assert_eq!(role(&strat), Some(ZoneRole::Resistance));
+        assert!(strat.zones()[0].is_active());
+
+        strat.update_lifecycle(PriceLevel(1150.0), 0, now);
+        assert_eq!(strat.zones()[0].breaks, 2);
+        assert_eq!(strat.zones()[0].state, ZoneState::Invalidated);
+        assert!(strat.take_changed());
+
+        // Invalidated zone does not alert anymore.
+        strat.analyze(PriceLevel(1200.0), now);
+        assert!(strat.analyze(PriceLevel(1050.0), now).is_empty());
+    }
+
+    #[test]
+    fn zone_expires() {
+        let mut strat = strat();
+        strat.zones[0].expires = Some(1000);
+
+        let now = Instant::now();
+
+        strat.update_lifecycle(PriceLevel(1200.0), 999, now);
+        assert!(strat.zones()[0].is_active());
+        strat.update_lifecycle(PriceLevel(1200.0), 1000, now);
+        assert_eq!(strat.zones()[0].state, ZoneState::Expired);
+    }
+
+    #[test]
+    fn zone_rules_choose_triggers_and_message() {
+        let mut strat = strat();
+        let zone = &mut strat.zones[0];
+        zone.note = Some(String::from("weekly open"));
+        zone.rules.triggers = Some(vec![AlertTrigger::Reject]);
+        zone.rules.sinks = Some(vec![AlertSink::Bell]);
+        zone.rules.message = Some(String::from("{event} {low}: {note}"));
+        let now = Instant::now();
+
+        strat.analyze(PriceLevel(1200.0), now);
+        // Entering does not alert, leaving on the same side does.
+        assert!(strat.analyze(PriceLevel(1050.0), now).is_empty());
+        let alerts = strat.analyze(PriceLevel(1150.0), now);
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].position(), Position::Buy);
+        assert_eq!(alerts[0].cause(), "was rejected from 1000: weekly open");
+        assert!(alerts[0].notifies(AlertSink::Bell));
+        assert!(!alerts[0].notifies(AlertSink::Panel));
+    }
+
+    #[test]
+    fn default_message_contains_note() {
+        let mut strat = strat();
+        strat.zones[0].note = Some(String::from("FOMC wick"));
+        let now = Instant::now();
+
+        strat.analyze(PriceLevel(900.0), now);
+        let alerts = strat.analyze(PriceLevel(1050.0), now);
+
+        assert_eq!(alerts[0].position(), Position::Sell);
+        assert_eq!(
+            alerts[0].cause(),
+            "Price entered High zone 1000 - 1100: FOMC wick"
+        );
+    }
+
+    #[test]
+    fn closest_zones_are_found_among_many() {
+        let zones = (0..500)
+            .map(|i| {
+                let low = 1000 + (i * 7 % 500) as i64 * 10;
+                Zone::new(
+                    ZonePriority::Low,
+                    Price::new(low, 0),
+                    Price::new(low + 5, 0),
+                )
+            })
+            .collect();
+        let mut strat = ZoneStrat::from_zones(zones);
+
+        strat.update_closest(PriceLevel(2007.0));
+        assert_eq!(strat.closest_upper().0, 2010.0);
+        assert_eq!(strat.closest_bottom().0, 2005.0);
+
+        // Inside of a zone the zone itself is neither above nor below.
+        strat.update_closest(PriceLevel(2012.0));
+        assert_eq!(strat.closest_upper().0, 2020.0);
+        assert_eq!(strat.closest_bottom().0, 2005.0);
+    }
+
+    #[test]
+    fn approaching_zone_alerts_once_until_price_moves_away() {
+        let mut strat = strat();
+        strat.set_proximity("1%".parse().unwrap());
+        let now = Instant::now();
+        let approaches = |strat: &mut ZoneStrat, price| {
+            strat.analyze(PriceLevel(price), now).len()
+        };
+
+        assert_eq!(approaches(&mut strat, 1200.0), 0);
+        // Within 1% of the zone's high.
+        assert_eq!(approaches(&mut strat, 1110.0), 1);
+        assert_eq!(approaches(&mut strat, 1105.0), 0);
+        assert_eq!(approaches(&mut strat, 1200.0), 0);
+        // Away and back again, but still cooling down.
+        assert_eq!(approaches(&mut strat, 1110.0), 0);
+        let later = now + ZoneStrat::ALERT_COOLDOWN;
+        strat.analyze(PriceLevel(1200.0), later);
+        let alerts = strat.analyze(PriceLevel(1110.0), later);
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].position(), Position::Buy);
+        assert_eq!(alerts[0].cause(), "Price approached High zone 1000 - 1100");
+    }
+
+    #[test]
+    fn atr_proximity_waits_for_enough_candles() {
+        let mut strat = strat();
+        strat.set_proximity(Proximity::Atr(2.0));
+        let now = Instant::now();
+
+        // Every candle ranges 10 around 1200 so the ATR settles at 10.
+        for _ in 0..14 {
+            strat.track_price(PriceLevel(1195.0));
+            strat.track_price(PriceLevel(1205.0));
+            assert!(strat.analyze(PriceLevel(1200.0), now).is_empty());
+        }
+        strat.track_price(PriceLevel(1200.0));
+        let alerts = strat.analyze(PriceLevel(1115.0), now);
+        assert_eq!(alerts.len(), 1);
+    }
+
+    #[test]
+    fn proximity_parses_all_kinds() {
+        assert_eq!("25".parse(), Ok(Proximity::Absolute(25.0)));
+        assert_eq!("0.5%".parse(), Ok(Proximity::Percent(0.5)));
+        assert_eq!("1.5ATR".parse(), Ok(Proximity::Atr(1.5)));
+        assert!("-1%".parse::<Proximity>().is_err());
+    }
+
+    #[test]
+    fn nested_zones_raise_one_alert() {
+        let mut strat = ZoneStrat::from_zones(vec![
+            Zone::new(
+                ZonePriority::Low,
+                Price::new(900, 0),
+                Price::new(1200, 0),
+            ),
+            Zone::new(
+                ZonePriority::High,
+                Price::new(1000, 0),
+                Price::new(1100, 0),
+            ),
+        ]);
+        let now = Instant::now();
+
+        strat.analyze(PriceLevel(1300.0), now);
+        let alerts = strat.analyze(PriceLevel(1050.0), now);
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].zone().unwrap().priority, ZonePriority::High);
+        assert_eq!(
+            alerts[0].cause(),
+            "Price entered High zone 1000 - 1100; \
+             also Price entered Low zone 900 - 1200"
+        );
+    }
+
+    #[test]
+    fn invalid_edits_are_rejected() {
+        let mut strat = strat();
+        strat.add_zone(Zone::new(
+            ZonePriority::Low,
+            Price::new(1200, 0),
+            Price::new(1300, 0),
+        ));
+
+        assert!(strat
+            .edit_zone(1, Price::new(1300, 0), Price::new(1200, 0))
+            .is_err());
+        assert!(strat
+            .edit_zone(1, Price::new(1000, 0), Price::new(1100, 0))
+            .is_err());
+        assert!(strat
+            .edit_zone(1, Price::new(1150, 0), Price::new(1250, 0))
+            .is_ok());
+        strat.update_closest(PriceLevel(1120.0));
+        assert_eq!(strat.closest_upper().0, 1150.0);
+    }
+}
@@ -0,0 +1,414 @@
+use std::fmt;
+
+use binance::{
+    api::Binance,
+    general::General,
+    model::{ExchangeInformation, Filters},
+};
+
+use crate::price::Price;
+
+/// Name of the trading pair, e.g. `ETHUSDT`.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct Symbol(String);
+
+impl Symbol {
+    /// Normalizes the way the users, the charting tools and the venues
+    /// write the pairs, `binance:eth/usdt` and `ETH-USDT` become `ETHUSDT`.
+    pub fn new(name: &str) -> Self {
+        let name = name.rsplit(':').next().unwrap_or(name);
+        Self(
+            name.chars()
+                .filter(|c| !matches!(c, '/' | '-' | '_') && !c.is_whitespace())
+                .map(|c| c.to_ascii_uppercase())
+                .collect(),
+        )
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Self {
+        Symbol::new(name)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(name: String) -> Self {
+        Symbol::new(&name)
+    }
+}
+
+impl From<Symbol> for String {
+    fn from(symbol: Symbol) -> Self {
+        symbol.0
+    }
+}
+
+/// Trading rules of the pair from the exchange info.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SymbolInfo {
+    pub symbol: Symbol,
+    pub base: String,
+    pub quote: String,
+    /// `TRADING` or the reason the pair can't be traded, e.g. `BREAK`
+    /// for the delisted pairs.
+    pub status: String,
+    /// Smallest price step.
+    pub tick_size: Option<Price>,
+    /// Smallest quantity step.
+    pub lot_size: Option<Price>,
+    pub min_quantity: Option<Price>,
+}
+
+impl SymbolInfo {
+    pub fn is_trading(&self) -> bool {
+        self.status == "TRADING"
+    }
+}
+
+impl From<&binance::model::Symbol> for SymbolInfo {
+    fn from(symbol: &binance::model::Symbol) -> Self {
+        // Zero means the exchange doesn't restrict the step.
+        let step = |value: &str| {
+            value.parse::<Price>().ok().filter(|p| p.is_positive())
+        };
+        let mut info = Self {
+            symbol: Symbol::new(&symbol.symbol),
+            base: symbol.base_asset.clone(),
+            quote: symbol.quote_asset.clone(),
+            status: symbol.status.clone(),
+            tick_size: None,
+            lot_size: None,
+            min_quantity: None,
+        };
+        for filter in &symbol.filters {
+            match filter {
+                Filters::PriceFilter { tick_size, .. } => {
+                    info.tick_size = step(tick_size)
+                }
+                Filters::LotSize {
+                    step_size, min_qty, ..
+                } => {
+                    info.lot_size = step(step_size);
+                    info.min_quantity = step(min_qty);
+                }
+                _ => (),
+            }
+        }
+        info
+    }
+}
+
+/// Symbol could not be used.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SymbolError {
+    /// The exchange doesn't list the symbol. Holds the closest matches.
+    Unknown(Symbol, Vec<Symbol>),
+    /// The pair is listed but can't be traded, most often because
+    /// it was delisted.
+    NotTrading(Symbol, String),
+}
+
+impl fmt::Display for SymbolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SymbolError::Unknown(symbol, matches) if matches.is_empty() => {
+                write!(f, "Unknown symbol {symbol}")
+            }
+            SymbolError::Unknown(symbol, matches) => {
+                let matches: Vec<&str> =
+                    matches.iter().map(Symbol::as_str).collect();
+                write!(
+                    f,
+                    "Unknown symbol {symbol}, did you mean {}?",
+                    matches.join(", ")
+                )
+            }
+            SymbolError::NotTrading(symbol, status) => write!(
+                f,
+                "{symbol} is not trading ({status}), the pair may be delisted"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SymbolError {}
+
+/// Trading rules of all the pairs listed on the exchange. The exchange info
+/// is large and rarely changes so it is cached in a file between the runs.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct SymbolCatalog {
+    /// Unix time in seconds when the exchange info was fetched.
+    fetched: u64,
+    symbols: Vec<SymbolInfo>,
+    /// Request weight per minute the exchange allows, missing in the
+    /// catalogs cached before it was kept.
+    #[serde(default)]
+    weight_limit: Option<u64>,
+}
+
+impl SymbolCatalog {
+    /// How long the cached exchange info is used before fetching it again.
+    const MAX_AGE: u64 = 24 * 60 * 60;
+
+    pub fn new(info: &ExchangeInformation, fetched: u64) -> Self {
+        let mut symbols: Vec<SymbolInfo> =
+            info.symbols.iter().map(SymbolInfo::from).collect();
+        symbols.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        let weight_limit = info
+            .rate_limits
+            .iter()
+            .filter(|limit| limit.rate_limit_type == "REQUEST_WEIGHT")
+            .filter_map(|limit| {
+                let minutes = match limit.interval.as_str() {
+                    "SECOND" => return None,
+                    "MINUTE" => 1,
+                    "HOUR" => 60,
+                    _ => 24 * 60,
+                } * u64::from(limit.interval_num.max(1));
+                Some(limit.limit / minutes)
+            })
+            .min();
+        Self {
+            fetched,
+            symbols,
+            weight_limit,
+        }
+    }
+
+    /// Reads the exchange info of all the pairs from Binance.
+    pub fn fetch(now: u64) -> Result<Self, String> {
+        let general: General = Binance::new(None, None);
+        let info = general.exchange_info().map_err(|e| e.to_string())?;
+        Ok(Self::new(&info, now))
+    }
+
+    /// Whether the catalog is recent enough to be used without fetching.
+    pub fn is_fresh(&self, now: u64) -> bool {
+        now.saturating_sub(self.fetched) < Self::MAX_AGE
+    }
+
+    /// Request weight per minute the exchange allows, the strictest one
+    /// if there are more limits.
+    pub fn weight_limit(&self) -> Option<u64> {
+        self.weight_limit
+    }
+
+    pub fn get(&self, symbol: &Symbol) -> Option<&SymbolInfo> {
+        self.symbols
+            .binary_search_by(|info| info.symbol.cmp(symbol))
+            .ok()
+            .map(|i| &self.symbols[i])
+    }
+
+    /// Returns the rules of the `name` pair if it can be traded.
+    pub fn validate(&self, name: &str) -> Result<&SymbolInfo, SymbolError> {
+        let symbol = Symbol::new(name);
+        match self.get(&symbol) {
+            Some(info) if info.is_trading() => Ok(info),
+            Some(info) => {
+                Err(SymbolError::NotTrading(symbol, info.status.clone()))
+            }
+            None => {
+                let matches = self
+                    .search(name, 3)
+                    .into_iter()
+                    .filter(|info| info.is_trading())
+                    .map(|info| info.symbol.clone())
+                    .collect();
+                Err(SymbolError::Unknown(symbol, matches))
+            }
+        }
+    }
+
+    /// Finds at most `limit` pairs matching the `query`. Exact matches come
+    /// first, then the pairs starting with the query, the pairs containing
+    /// it and finally the pairs a typo or two away. Traded pairs come before
+    /// the others with the same match.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&SymbolInfo> {
+        let query = Symbol::new(query);
+        let query = query.as_str();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let max_typos = (query.len() / 3).max(1);
+        let mut matches: Vec<(usize, &SymbolInfo)> = self
+            .symbols
+            .iter()
+            .filter_map(|info| {
+                let name = info.symbol.as_str();
+                let rank = if name == query {
+                    0
+                } else if name.starts_with(query) {
+                    1
+                } else if name.contains(query) {
+                    2
+                } else {
+                    let typos = edit_distance(name, query);
+                    if typos > max_typos {
+                        return None;
+                    }
+                    2 + typos
+                };
+                Some((rank, info))
+            })
+            .collect();
+        matches.sort_by_key(|(rank, info)| {
+            (*rank, !info.is_trading(), info.symbol.as_str().len())
+        });
+        matches
+            .into_iter()
+            .take(limit)
+            .map(|(_, info)| info)
+            .collect()
+    }
+}
+
+/// Number of single character insertions, deletions and substitutions
+/// which turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] =
+                substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Symbol, SymbolCatalog, SymbolError};
+    use crate::price::Price;
+
+    fn symbol(name: &str, status: &str, tick: &str) -> String {
+        let (base, quote) = name.split_at(name.len() - 4);
+        format!(
+            r#"{{"symbol": "{name}", "status": "{status}",
+                "baseAsset": "{base}", "baseAssetPrecision": 8,
+                "quoteAsset": "{quote}", "quotePrecision": 8,
+                "orderTypes": ["LIMIT", "MARKET"], "icebergAllowed": true,
+                "isSpotTradingAllowed": true,
+                "isMarginTradingAllowed": false,
+                "filters": [
+                    {{"filterType": "PRICE_FILTER", "minPrice": "{tick}",
+                      "maxPrice": "1000000.00000000", "tickSize": "{tick}"}},
+                    {{"filterType": "LOT_SIZE", "minQty": "0.00010000",
+                      "maxQty": "9000.00000000", "stepSize": "0.00010000"}}
+                ]}}"#
+        )
+    }
+
+    fn catalog() -> SymbolCatalog {
+        let symbols = [
+            symbol("ETHUSDT", "TRADING", "0.01000000"),
+            symbol("ETHBUSD", "BREAK", "0.01000000"),
+            symbol("BTCUSDT", "TRADING", "0.01000000"),
+            symbol("SHIBUSDT", "TRADING", "0.00000001"),
+        ];
+        let json = format!(
+            r#"{{"timezone": "UTC", "serverTime": 0, "rateLimits": [
+                    {{"rateLimitType": "REQUEST_WEIGHT", "interval": "MINUTE",
+                      "intervalNum": 1, "limit": 6000}},
+                    {{"rateLimitType": "REQUEST_WEIGHT", "interval": "MINUTE",
+                      "intervalNum": 5, "limit": 20000}},
+                    {{"rateLimitType": "ORDERS", "interval": "SECOND",
+                      "intervalNum": 10, "limit": 100}}
+                 ],
+                 "symbols": [{}]}}"#,
+            symbols.join(",")
+        );
+        SymbolCatalog::new(&serde_json::from_str(&json).unwrap(), 0)
+    }
+
+    #[test]
+    fn symbols_are_normalized() {
+        assert_eq!(Symbol::new("binance:eth/usdt").as_str(), "ETHUSDT");
+        assert_eq!(Symbol::new(" eth-usdt ").as_str(), "ETHUSDT");
+        assert_eq!(Symbol::new("bybit:SOL_USDC").as_str(), "SOLUSDC");
+        // The venue names of the assets are left to the venues.
+        assert_eq!(Symbol::new("kraken:XBT/USDT").as_str(), "XBTUSDT");
+    }
+
+    #[test]
+    fn validation_reads_rules_and_rejects_delisted() {
+        let catalog = catalog();
+
+        let info = catalog.validate("eth/usdt").unwrap();
+        assert_eq!((info.base.as_str(), info.quote.as_str()), ("ETH", "USDT"));
+        assert_eq!(info.tick_size, Some(Price::new(1, 2)));
+        assert_eq!(info.lot_size, Some(Price::new(1, 4)));
+        assert_eq!(
+            catalog.validate("SHIBUSDT").unwrap().tick_size,
+            Some(Price::new(1, 8))
+        );
+
+        let err = catalog.validate("ETHBUSD").unwrap_err();
+        assert!(
+            matches!(err, SymbolError::NotTrading(_, ref s) if s == "BREAK")
+        );
+        assert_eq!(
+            catalog.validate("ETHUSTD").unwrap_err().to_string(),
+            "Unknown symbol ETHUSTD, did you mean ETHUSDT?"
+        );
+        assert_eq!(
+            catalog.validate("XRPEUR").unwrap_err().to_string(),
+            "Unknown symbol XRPEUR"
+        );
+    }
+
+    #[test]
+    fn strictest_weight_limit_is_kept() {
+        let catalog = catalog();
+        // 20000 per 5 minutes is stricter than 6000 per minute.
+        assert_eq!(catalog.weight_limit(), Some(4000));
+
+        let old: SymbolCatalog =
+            serde_json::from_str(r#"{"fetched": 0, "symbols": []}"#).unwrap();
+        assert_eq!(old.weight_limit(), None);
+    }
+
+    #[test]
+    fn search_ranks_matches() {
+        let catalog = catalog();
+        let names = |query: &str| -> Vec<String> {
+            catalog
+                .search(query, 5)
+                .into_iter()
+                .map(|info| info.symbol.to_string())
+                .collect()
+        };
+
+        // Traded pairs before the delisted ones.
+        assert_eq!(names("eth"), ["ETHUSDT", "ETHBUSD"]);
+        assert_eq!(names("usdt"), ["BTCUSDT", "ETHUSDT", "SHIBUSDT"]);
+        assert_eq!(names("BTCUSDT"), ["BTCUSDT", "ETHUSDT"]);
+        assert_eq!(names("BTCUDST"), ["BTCUSDT"]);
+        assert!(names("DOGE").is_empty());
+    }
+}
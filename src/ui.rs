@@ -1,31 +1,63 @@
+use std::time::Instant;
+
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
     text::{Span, Spans},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{
+        Block, Borders, List, ListItem, ListState, Paragraph, Sparkline, Wrap,
+    },
     Frame,
 };
 
-use crate::{alert::Alert, bot::MarketBot};
+use crate::{
+    alert::{Alert, AlertSink, Position},
+    bot::{DetectionState, MarketBot, PriceLevel},
+    config::{Theme, UiConfig},
+    console::InputMode,
+    depth::Liquidity,
+    execution::OrderStatus,
+    input::InputHandler,
+    price::{format_level, Price},
+    strategy::{ZonePriority, ZoneState},
+};
 
 pub struct UI {
     // Static objects
     commands: CommandsPar,
+    input: InputPar,
 
     // Dynamic objects
     live_price: LivePricePar,
     alerts: AlertList,
     zone_list: ZoneList,
+    suggestions: SuggestionList,
+    paper: PaperPar,
+    execution: ExecutionPar,
+    risk: RiskPar,
+
+    /// List the keys select in the control mode.
+    focus: Focus,
+    layout: UiConfig,
+    theme: Theme,
 }
 
 impl UI {
     pub fn init() -> Self {
         Self {
             commands: CommandsPar::new(),
+            input: InputPar::new(),
             live_price: LivePricePar::new(),
             alerts: AlertList::new(),
             zone_list: ZoneList::new(),
+            suggestions: SuggestionList::new(),
+            paper: PaperPar::new(),
+            execution: ExecutionPar::new(),
+            risk: RiskPar::new(),
+            focus: Focus::Suggestions,
+            layout: UiConfig::default(),
+            theme: Theme::default(),
         }
     }
 
@@ -34,6 +66,67 @@ impl UI {
         self.live_price.update(data);
         self.alerts.update(data);
         self.zone_list.update(data);
+        self.suggestions.update(data);
+        self.paper.update(data);
+        self.execution.update(data);
+        self.risk.zone = self.zone_list.selected;
+        self.risk.update(data);
+    }
+
+    /// Updates the input box with the current state of the input buffer.
+    pub fn update_input(&mut self, input: &InputHandler) {
+        self.input.update(input);
+    }
+
+    pub fn update_status(&mut self, status: Option<String>) {
+        self.input.status = status;
+    }
+
+    pub fn update_input_mode(&mut self, mode: InputMode) {
+        self.input.mode = mode;
+        self.update_focus();
+    }
+
+    /// Switches the control mode between the suggested zones
+    /// and the zone list.
+    pub fn switch_focus(&mut self) {
+        self.focus = match self.focus {
+            Focus::Suggestions => Focus::Zones,
+            Focus::Zones => Focus::Suggestions,
+        };
+        self.update_focus();
+    }
+
+    fn update_focus(&mut self) {
+        let control = self.input.mode == InputMode::Control;
+        self.suggestions.active = control && self.focus == Focus::Suggestions;
+        self.zone_list.active = control && self.focus == Focus::Zones;
+    }
+
+    /// Moves the selection in the focused list up or `down`.
+    pub fn select(&mut self, down: bool) {
+        match self.focus {
+            Focus::Suggestions => self.suggestions.select(down),
+            Focus::Zones => self.zone_list.select(down),
+        }
+    }
+
+    /// Selected suggested zone while the suggestions are focused.
+    pub fn selected_suggestion(&self) -> Option<usize> {
+        match self.focus {
+            Focus::Suggestions => self.suggestions.selected,
+            Focus::Zones => None,
+        }
+    }
+
+    /// Changes the sizes of the UI objects. Takes effect
+    /// with the next [`UI::update_layout`].
+    pub fn set_layout(&mut self, layout: UiConfig) {
+        self.layout = layout;
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
     }
 
     /// Updates layout and positions of the UI objects.
@@ -42,74 +135,322 @@ impl UI {
     }
 
     pub fn render<B: Backend>(&self, frame: &mut Frame<B>) {
-        self.commands.render(frame);
-        self.live_price.render(frame);
-        self.alerts.render(frame);
-        self.zone_list.render(frame);
+        let theme = &self.theme;
+        self.commands.render(frame, theme);
+        self.input.render(frame, theme);
+        self.live_price.render(frame, theme);
+        self.alerts.render(frame, theme);
+        self.zone_list.render(frame, theme);
+        self.suggestions.render(frame, theme);
+        self.paper.render(frame, theme);
+        self.execution.render(frame, theme);
+        self.risk.render(frame, theme);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Suggestions,
+    Zones,
+}
+
+/// Moves the `selected` item of the list of `len` items up or `down`.
+fn move_selection(
+    selected: Option<usize>,
+    len: usize,
+    down: bool,
+) -> Option<usize> {
+    let last = len.checked_sub(1)?;
+    Some(match selected {
+        Some(i) if down => (i + 1).min(last),
+        Some(i) => i.saturating_sub(1),
+        None => 0,
+    })
+}
+
+/// Keeps the selection within the list of `len` items.
+fn clamp_selection(selected: Option<usize>, len: usize) -> Option<usize> {
+    match len {
+        0 => None,
+        len => Some(selected.unwrap_or(0).min(len - 1)),
+    }
+}
+
+fn focus_style(active: bool, theme: &Theme) -> Style {
+    if active {
+        Style::default().fg(theme.focus)
+    } else {
+        Style::default()
+    }
+}
+
+fn priority_color(priority: ZonePriority, theme: &Theme) -> Color {
+    match priority {
+        ZonePriority::High => theme.high,
+        ZonePriority::Medium => theme.medium,
+        ZonePriority::Low => theme.low,
+    }
+}
+
+fn position_color(position: Position, theme: &Theme) -> Color {
+    match position {
+        Position::Buy => theme.up,
+        Position::Sell => theme.down,
     }
 }
 
 #[derive(Debug)]
 struct ZoneList {
     area: Rect,
-    visible: bool,
+
+    /// Whether the keys control this list.
+    active: bool,
+    /// Priority, bounds, state, whether the zone is active and the order
+    /// book liquidity inside it.
+    zones: Vec<(ZonePriority, String, String, bool, Option<Liquidity>)>,
+    selected: Option<usize>,
 }
 
 impl ZoneList {
     fn new() -> Self {
         Self {
             area: Rect::default(),
-            visible: true,
+            active: false,
+            zones: Vec::new(),
+            selected: None,
         }
     }
+
+    fn select(&mut self, down: bool) {
+        self.selected = move_selection(self.selected, self.zones.len(), down);
+    }
 }
 
 impl StaticObject for ZoneList {
-    fn render<B: Backend>(&self, frame: &mut Frame<B>) {
-        let block = Block::default().borders(Borders::all()).title("Zone List");
-        frame.render_widget(block, self.area);
+    fn render<B: Backend>(&self, frame: &mut Frame<B>, theme: &Theme) {
+        let items: Vec<ListItem> = self
+            .zones
+            .iter()
+            .map(|(priority, bounds, state, active, liquidity)| {
+                let (priority_style, state_style, bid_style, ask_style) =
+                    if *active {
+                        (
+                            Style::default()
+                                .fg(priority_color(*priority, theme)),
+                            Style::default(),
+                            Style::default().fg(theme.up),
+                            Style::default().fg(theme.down),
+                        )
+                    } else {
+                        let inactive = Style::default().fg(Color::DarkGray);
+                        (inactive, inactive, inactive, inactive)
+                    };
+                let mut spans = vec![
+                    Span::styled(
+                        format!("{:<6}", format!("{priority:?}")),
+                        priority_style,
+                    ),
+                    Span::styled(bounds.as_str(), state_style),
+                    Span::styled(format!(" {state}"), state_style),
+                ];
+                if let Some(liquidity) = liquidity {
+                    spans.push(Span::styled(
+                        format!(" bid {:.2}", liquidity.bid),
+                        bid_style,
+                    ));
+                    spans.push(Span::styled(
+                        format!(" ask {:.2}", liquidity.ask),
+                        ask_style,
+                    ));
+                }
+                ListItem::new(Spans::from(spans))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::all())
+                    .border_style(focus_style(self.active, theme))
+                    .title("Zone List"),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        let mut state = ListState::default();
+        state.select(self.selected);
+        frame.render_stateful_widget(list, self.area, &mut state);
     }
 
     fn position_area(&mut self, terminal_size: Rect) {
         self.area = terminal_size;
     }
+}
+
+impl DynamicObject for ZoneList {
+    fn update(&mut self, data: &MarketBot) {
+        let tick = data.tick_size();
+        self.zones = data
+            .zone_strat()
+            .zones()
+            .iter()
+            .enumerate()
+            .map(|(i, z)| {
+                let state = match (z.state, z.role) {
+                    (ZoneState::Active, Some(role)) => format!(
+                        "{role:?} ({} touches, {} breaks)",
+                        z.touches, z.breaks
+                    ),
+                    (ZoneState::Active, None) => String::from("New"),
+                    (state, _) => format!("{state:?}"),
+                };
+                let state = z
+                    .tags
+                    .iter()
+                    .fold(state, |state, tag| format!("{state} #{tag}"));
+                (
+                    z.priority,
+                    format!(
+                        "{}. {} - {}",
+                        i + 1,
+                        z.low.format(tick),
+                        z.high.format(tick)
+                    ),
+                    state,
+                    z.is_active(),
+                    data.zone_liquidity(z),
+                )
+            })
+            .collect();
+        // Nothing is selected until the user starts selecting.
+        if self.selected.is_some() {
+            self.selected = clamp_selection(self.selected, self.zones.len());
+        }
+    }
+}
+
+/// Zones proposed by the zone detection which the
+/// user can accept or reject in the control mode.
+#[derive(Debug)]
+struct SuggestionList {
+    area: Rect,
+
+    /// Whether the keys control this list.
+    active: bool,
+    status: String,
+    suggestions: Vec<(ZonePriority, String)>,
+    selected: Option<usize>,
+}
+
+impl SuggestionList {
+    fn new() -> Self {
+        Self {
+            area: Rect::default(),
+            active: false,
+            status: String::from("type 'detect' to find zones"),
+            suggestions: Vec::new(),
+            selected: None,
+        }
+    }
 
-    fn set_visibility(&mut self, visible: bool) {
-        self.visible = visible;
+    fn select(&mut self, down: bool) {
+        self.selected =
+            move_selection(self.selected, self.suggestions.len(), down);
     }
+}
+
+impl StaticObject for SuggestionList {
+    fn render<B: Backend>(&self, frame: &mut Frame<B>, theme: &Theme) {
+        let items: Vec<ListItem> = self
+            .suggestions
+            .iter()
+            .map(|(priority, text)| {
+                ListItem::new(Spans::from(vec![
+                    Span::styled(
+                        format!("{:<6}", format!("{priority:?}")),
+                        Style::default().fg(priority_color(*priority, theme)),
+                    ),
+                    Span::raw(text),
+                ]))
+            })
+            .collect();
 
-    fn is_visible(&self) -> bool {
-        self.visible
+        let border_style = focus_style(self.active, theme);
+        let title = format!("Suggested Zones ({})", self.status);
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::all())
+                    .border_style(border_style)
+                    .title(title),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        let mut state = ListState::default();
+        state.select(self.selected);
+        frame.render_stateful_widget(list, self.area, &mut state);
+    }
+
+    fn position_area(&mut self, terminal_size: Rect) {
+        self.area = terminal_size;
     }
 }
 
-impl DynamicObject for ZoneList {
-    fn update(&mut self, bot: &MarketBot) {
-        todo!()
+impl DynamicObject for SuggestionList {
+    fn update(&mut self, data: &MarketBot) {
+        let tick = data.tick_size();
+        self.suggestions = data
+            .suggestions()
+            .iter()
+            .map(|s| {
+                let text = format!(
+                    "{} - {} ({} touches)",
+                    s.zone.low.format(tick),
+                    s.zone.high.format(tick),
+                    s.touches
+                );
+                (s.zone.priority, text)
+            })
+            .collect();
+        self.status = match data.detection_state() {
+            DetectionState::Running(_) => String::from("detecting..."),
+            DetectionState::Failed(e) => format!("detection failed: {e}"),
+            DetectionState::Idle if self.suggestions.is_empty() => {
+                String::from("type 'detect' to find zones")
+            }
+            DetectionState::Idle => {
+                String::from("TAB, then a/r to accept/reject")
+            }
+        };
+        self.selected = clamp_selection(self.selected, self.suggestions.len());
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 struct AlertList {
     area: Rect,
-    visible: bool,
+    /// Alerts shown in the panel, newest first.
     alerts: Vec<Alert>,
+    /// Time the alert ages are measured to.
+    now: Instant,
 }
 
 impl AlertList {
     fn new() -> Self {
         Self {
             area: Rect::default(),
-            visible: true,
             alerts: Vec::new(),
+            now: Instant::now(),
         }
     }
 }
 
 impl StaticObject for AlertList {
-    fn render<B: Backend>(&self, frame: &mut Frame<B>) {
-        let text = vec![];
-
+    fn render<B: Backend>(&self, frame: &mut Frame<B>, theme: &Theme) {
+        let text: Vec<Spans> = self
+            .alerts
+            .iter()
+            .flat_map(|alert| alert.text(self.now, theme))
+            .collect();
         let paragraph = Paragraph::new(text)
             .block(Block::default().borders(Borders::all()).title("Alerts"))
             .alignment(Alignment::Left);
@@ -119,102 +460,535 @@ impl StaticObject for AlertList {
     fn position_area(&mut self, terminal_size: Rect) {
         self.area = terminal_size;
     }
-
-    fn set_visibility(&mut self, visible: bool) {
-        self.visible = visible;
-    }
-
-    fn is_visible(&self) -> bool {
-        self.visible
-    }
 }
 
 impl DynamicObject for AlertList {
     fn update(&mut self, data: &MarketBot) {
-        todo!()
+        self.now = data.clock().now();
+        self.alerts = data
+            .alerts()
+            .iter()
+            .rev()
+            .filter(|alert| alert.notifies(AlertSink::Panel))
+            .cloned()
+            .collect();
     }
 }
 
 #[derive(Debug)]
 struct LivePricePar {
     area: Rect,
-    visible: bool,
 
     symbol: String,
     price: String,
+    change: PriceChange,
+    /// Percentage distance to the closest zone above the price.
+    upper_distance: String,
+    /// Percentage distance to the closest zone below the price.
+    bottom_distance: String,
+    /// Recent prices scaled for the [`Sparkline`] widget.
+    sparkline: Vec<u64>,
     // TODO volume:
     // 24h change:
     // 7d change:
 }
 
 impl LivePricePar {
+    /// Resolution of the sparkline bars.
+    const SPARKLINE_MAX: u64 = 100;
+
     fn new() -> Self {
         Self {
             area: Rect::default(),
-            visible: true,
             symbol: String::from("{Symbol}"),
             price: String::from("{Price}"),
+            change: PriceChange::Unchanged,
+            upper_distance: String::from("-"),
+            bottom_distance: String::from("-"),
+            sparkline: Vec::new(),
+        }
+    }
+
+    fn format_distance(price: PriceLevel, zone: PriceLevel) -> String {
+        if zone.0.is_nan() || price.0.is_nan() {
+            String::from("-")
+        } else {
+            format!("{:+.2}%", price.distance_percent(zone))
         }
     }
+
+    /// Scales the prices between the lowest and the highest
+    /// one so small moves are still visible on the sparkline.
+    fn scale_history<'a, I>(history: I) -> Vec<u64>
+    where
+        I: Iterator<Item = &'a Price> + Clone,
+    {
+        let (min, max) = history
+            .clone()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), p| {
+                (min.min(p.to_f64()), max.max(p.to_f64()))
+            });
+        let range = max - min;
+        history
+            .map(|p| {
+                if range > 0.0 {
+                    let offset = p.to_f64() - min;
+                    (offset / range * Self::SPARKLINE_MAX as f64) as u64
+                } else {
+                    Self::SPARKLINE_MAX / 2
+                }
+            })
+            .collect()
+    }
 }
 
 impl StaticObject for LivePricePar {
-    fn render<B: Backend>(&self, frame: &mut Frame<B>) {
+    fn render<B: Backend>(&self, frame: &mut Frame<B>, theme: &Theme) {
+        let block =
+            Block::default().borders(Borders::all()).title("Live Price");
+        let inner = block.inner(self.area);
+        frame.render_widget(block, self.area);
+
+        let areas = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(60),
+                Constraint::Percentage(40),
+            ])
+            .split(inner);
+
+        let price_style = match self.change {
+            PriceChange::Up => Style::default().fg(theme.up),
+            PriceChange::Down => Style::default().fg(theme.down),
+            PriceChange::Unchanged => Style::default(),
+        };
         let text = vec![Spans::from(vec![
             Span::styled(
                 &self.symbol,
                 Style::default().add_modifier(Modifier::BOLD),
             ),
             Span::raw(": "),
-            Span::raw(&self.price),
+            Span::styled(&self.price, price_style),
+            Span::raw("  \u{25b2} "),
+            Span::raw(&self.upper_distance),
+            Span::raw("  \u{25bc} "),
+            Span::raw(&self.bottom_distance),
         ])];
+        let paragraph = Paragraph::new(text).alignment(Alignment::Center);
+        frame.render_widget(paragraph, areas[0]);
 
-        let paragraph = Paragraph::new(text)
-            .block(Block::default().borders(Borders::all()).title("Live Price"))
-            .alignment(Alignment::Center);
+        let sparkline = Sparkline::default()
+            .data(&self.sparkline)
+            .max(Self::SPARKLINE_MAX)
+            .style(price_style);
+        frame.render_widget(sparkline, areas[1]);
+    }
+
+    fn position_area(&mut self, terminal_size: Rect) {
+        self.area = terminal_size;
+    }
+}
+
+impl DynamicObject for LivePricePar {
+    fn update(&mut self, data: &MarketBot) {
+        let price = data.get_price();
+        let previous = data.get_previous_price();
+        let zones = data.zone_strat();
+
+        self.symbol = match data.symbol_info() {
+            Some(info) => format!("{}/{}", info.base, info.quote),
+            None => data.get_symbol().to_string(),
+        };
+        self.price = if price.0.is_nan() {
+            String::from("{Price}")
+        } else {
+            format_level(price, data.tick_size())
+        };
+        self.change = if price.0 > previous.0 {
+            PriceChange::Up
+        } else if price.0 < previous.0 {
+            PriceChange::Down
+        } else {
+            PriceChange::Unchanged
+        };
+        self.upper_distance =
+            Self::format_distance(price, zones.closest_upper());
+        self.bottom_distance =
+            Self::format_distance(price, zones.closest_bottom());
+
+        let history = data.price_history();
+        // Show only as many prices as there is space for.
+        let width = self.area.width.saturating_sub(2) as usize * 2 / 5;
+        let skip = history.len().saturating_sub(width);
+        self.sparkline = Self::scale_history(history.iter().skip(skip));
+    }
+}
+
+/// Equity and open positions of the paper trading.
+#[derive(Debug)]
+struct PaperPar {
+    area: Rect,
+
+    summary: String,
+    /// Change of the equity since the start in percent.
+    change: f64,
+    /// Direction, description and the profit of each open position.
+    positions: Vec<(Position, String, f64)>,
+}
 
+impl PaperPar {
+    const HEIGHT: u16 = 6;
+
+    fn new() -> Self {
+        Self {
+            area: Rect::default(),
+            summary: String::new(),
+            change: 0.0,
+            positions: Vec::new(),
+        }
+    }
+}
+
+fn pnl_color(pnl: f64, theme: &Theme) -> Color {
+    if pnl > 0.0 {
+        theme.up
+    } else if pnl < 0.0 {
+        theme.down
+    } else {
+        Color::Reset
+    }
+}
+
+impl StaticObject for PaperPar {
+    fn render<B: Backend>(&self, frame: &mut Frame<B>, theme: &Theme) {
+        let mut text = vec![Spans::from(vec![
+            Span::raw(&self.summary),
+            Span::styled(
+                format!(" ({:+.2}%)", self.change),
+                Style::default().fg(pnl_color(self.change, theme)),
+            ),
+        ])];
+        text.extend(self.positions.iter().map(|(position, text, pnl)| {
+            let color = position_color(*position, theme);
+            Spans::from(vec![
+                Span::styled(
+                    format!("{:<5}", format!("{position:?}")),
+                    Style::default().fg(color),
+                ),
+                Span::raw(text),
+                Span::styled(
+                    format!(" {pnl:+.2}"),
+                    Style::default().fg(pnl_color(*pnl, theme)),
+                ),
+            ])
+        }));
+
+        let paragraph = Paragraph::new(text).block(
+            Block::default()
+                .borders(Borders::all())
+                .title("Paper Trading"),
+        );
         frame.render_widget(paragraph, self.area);
     }
 
     fn position_area(&mut self, terminal_size: Rect) {
         self.area = terminal_size;
     }
+}
+
+impl DynamicObject for PaperPar {
+    fn update(&mut self, data: &MarketBot) {
+        let trader = data.paper_trader();
+        let price = data.get_price();
+        self.summary = format!(
+            "Equity: {:.2} Realized: {:+.2} Won: {}/{}",
+            trader.equity(),
+            trader.realized_pnl(),
+            trader.wins(),
+            trader.closed_positions().len(),
+        );
+        self.change = trader.equity_change();
+        self.positions = trader
+            .open_positions()
+            .iter()
+            .rev()
+            .map(|p| {
+                let target =
+                    p.target.map_or(String::from("-"), |t| t.0.to_string());
+                let text = format!(
+                    "{:.4} @ {} SL {} TP {}",
+                    p.quantity, p.entry.0, p.stop.0, target
+                );
+                let pnl = if price.0.is_nan() { 0.0 } else { p.pnl(price) };
+                (p.position, text, pnl)
+            })
+            .collect();
+    }
+}
+
+/// Trade planned at the zone selected in the zone list.
+#[derive(Debug)]
+struct RiskPar {
+    area: Rect,
+
+    /// Index of the selected zone.
+    zone: Option<usize>,
+    title: String,
+    /// Side of the planned trade, `None` if no zone is selected.
+    side: Option<Position>,
+    /// Entry, stop loss and target following the side.
+    order: String,
+    size: String,
+}
+
+impl RiskPar {
+    const HEIGHT: u16 = 4;
+
+    fn new() -> Self {
+        Self {
+            area: Rect::default(),
+            zone: None,
+            title: String::from("Risk"),
+            side: None,
+            order: String::new(),
+            size: String::new(),
+        }
+    }
+}
 
-    fn set_visibility(&mut self, visible: bool) {
-        self.visible = visible;
+impl StaticObject for RiskPar {
+    fn render<B: Backend>(&self, frame: &mut Frame<B>, theme: &Theme) {
+        let text = match self.side {
+            Some(side) => vec![
+                Spans::from(vec![
+                    Span::styled(
+                        format!("{side:?}"),
+                        Style::default().fg(position_color(side, theme)),
+                    ),
+                    Span::raw(self.order.as_str()),
+                ]),
+                Spans::from(self.size.as_str()),
+            ],
+            None => vec![Spans::from(self.order.as_str())],
+        };
+        let paragraph = Paragraph::new(text).block(
+            Block::default()
+                .borders(Borders::all())
+                .title(self.title.as_str()),
+        );
+        frame.render_widget(paragraph, self.area);
     }
 
-    fn is_visible(&self) -> bool {
-        self.visible
+    fn position_area(&mut self, terminal_size: Rect) {
+        self.area = terminal_size;
     }
 }
 
-impl DynamicObject for LivePricePar {
+impl DynamicObject for RiskPar {
     fn update(&mut self, data: &MarketBot) {
-        //self.symbol = data
-        todo!()
+        let tick = data.tick_size();
+        let Some((i, plan)) =
+            self.zone.and_then(|i| Some((i, data.risk_plan(i)?)))
+        else {
+            self.title = String::from("Risk");
+            self.side = None;
+            self.order =
+                String::from("Select a zone: TAB, Z, then \u{2191}/\u{2193}");
+            return;
+        };
+        self.title = format!("Risk - zone {}", i + 1);
+        let target = match (plan.target, plan.reward) {
+            (Some(target), Some(reward)) => {
+                format!(" TP {} ({reward:.2}R)", format_level(target, tick))
+            }
+            _ => String::from(" TP -"),
+        };
+        let distance = if plan.distance.is_nan() {
+            String::from("-")
+        } else {
+            format!("{:+.2}%", plan.distance)
+        };
+        self.side = Some(plan.side);
+        self.order = format!(
+            " {} SL {}{target}",
+            format_level(plan.entry, tick),
+            format_level(plan.stop, tick)
+        );
+        self.size = format!(
+            "Size {:.4} risking {:.2}, entry {distance} from the price",
+            plan.quantity, plan.risk
+        );
+    }
+}
+
+/// Mode of the order execution and the order waiting for the confirmation
+/// or the last placed order.
+#[derive(Debug)]
+struct ExecutionPar {
+    area: Rect,
+
+    title: String,
+    text: String,
+    /// Whether the order waits for the confirmation.
+    pending: bool,
+}
+
+impl ExecutionPar {
+    const HEIGHT: u16 = 3;
+
+    fn new() -> Self {
+        Self {
+            area: Rect::default(),
+            title: String::from("Execution"),
+            text: String::from("Off, start with --execute"),
+            pending: false,
+        }
+    }
+}
+
+impl StaticObject for ExecutionPar {
+    fn render<B: Backend>(&self, frame: &mut Frame<B>, _theme: &Theme) {
+        let style = if self.pending {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+        let paragraph = Paragraph::new(Span::styled(&self.text, style)).block(
+            Block::default()
+                .borders(Borders::all())
+                .title(self.title.as_str()),
+        );
+        frame.render_widget(paragraph, self.area);
+    }
+
+    fn position_area(&mut self, terminal_size: Rect) {
+        self.area = terminal_size;
+    }
+}
+
+impl DynamicObject for ExecutionPar {
+    fn update(&mut self, data: &MarketBot) {
+        let Some(executor) = data.executor() else {
+            return;
+        };
+        let mode = if executor.is_dry_run() {
+            "dry run"
+        } else {
+            "live"
+        };
+        let position = executor.position();
+        self.title = if executor.is_killed() {
+            format!("Execution ({mode}) - KILLED, type 'resume'")
+        } else {
+            format!("Execution ({mode}, position {position})")
+        };
+        self.pending = executor.pending().is_some();
+        self.text = match (executor.pending(), executor.last_order()) {
+            (Some(order), _) => format!("Confirm {order}? TAB, then y/n"),
+            (None, Some(order)) => {
+                let status = match &order.status {
+                    OrderStatus::DryRun => String::from("Logged"),
+                    OrderStatus::Placed { id } => format!("Placed #{id}"),
+                    OrderStatus::Failed { error } => {
+                        format!("Failed ({error})")
+                    }
+                };
+                format!(
+                    "{status} {:?} {} @ {}",
+                    order.side, order.quantity, order.price
+                )
+            }
+            (None, None) => String::from("No orders yet"),
+        };
+    }
+}
+
+/// Direction of the price move compared to the previous tick.
+#[derive(Debug, Clone, Copy)]
+enum PriceChange {
+    Up,
+    Down,
+    Unchanged,
+}
+
+/// Single line box showing what the user is typing.
+#[derive(Debug)]
+struct InputPar {
+    area: Rect,
+
+    text: String,
+    cursor: usize,
+    mode: InputMode,
+    /// Result of the last command.
+    status: Option<String>,
+}
+
+impl InputPar {
+    const HEIGHT: u16 = 3;
+
+    fn new() -> Self {
+        Self {
+            area: Rect::default(),
+            text: String::new(),
+            cursor: 0,
+            mode: InputMode::Editing,
+            status: None,
+        }
+    }
+
+    fn update(&mut self, input: &InputHandler) {
+        self.text = input.current_input().to_owned();
+        self.cursor = input.get_cursor_position();
+    }
+}
+
+impl StaticObject for InputPar {
+    fn render<B: Backend>(&self, frame: &mut Frame<B>, _theme: &Theme) {
+        let mode = match self.mode {
+            InputMode::Editing => "Input",
+            InputMode::Control => "Control mode (TAB - edit)",
+        };
+        let title = match &self.status {
+            Some(status) => format!("{mode} - {status}"),
+            None => mode.to_owned(),
+        };
+        let paragraph = Paragraph::new(self.text.as_str())
+            .block(Block::default().borders(Borders::all()).title(title));
+        frame.render_widget(paragraph, self.area);
+
+        // Place the cursor inside of the borders
+        if self.mode == InputMode::Editing
+            && self.area.width > 2
+            && self.area.height > 2
+        {
+            let max_x = self.area.x + self.area.width - 2;
+            let x = (self.area.x + 1 + self.cursor as u16).min(max_x);
+            frame.set_cursor(x, self.area.y + 1);
+        }
+    }
+
+    fn position_area(&mut self, terminal_size: Rect) {
+        self.area = terminal_size;
     }
 }
 
 #[derive(Debug)]
 struct CommandsPar {
     area: Rect,
-    visible: bool,
 }
 
 impl CommandsPar {
-    const HEIGHT: u16 = 6;
+    const HEIGHT: u16 = 7;
 
     fn new() -> Self {
         Self {
             area: Rect::default(),
-            visible: true,
         }
     }
 }
 
 impl StaticObject for CommandsPar {
-    fn render<B: Backend>(&self, frame: &mut Frame<B>) {
+    fn render<B: Backend>(&self, frame: &mut Frame<B>, _theme: &Theme) {
         // TODO maybe add custom owned struct instead of creating a new one
         // BOLD IS NOT VISIBLE
         let text = vec![
@@ -239,6 +1013,20 @@ impl StaticObject for CommandsPar {
                 ),
                 Span::raw(" - add a new zone"),
             ]),
+            Spans::from(vec![
+                Span::styled(
+                    "TAB",
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" - review the suggested zones"),
+            ]),
+            Spans::from(vec![
+                Span::styled(
+                    "Z",
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" - switch to the zone list"),
+            ]),
         ];
 
         let paragraph = Paragraph::new(text)
@@ -252,14 +1040,6 @@ impl StaticObject for CommandsPar {
     fn position_area(&mut self, terminal_size: Rect) {
         self.area = terminal_size;
     }
-
-    fn set_visibility(&mut self, visible: bool) {
-        self.visible = visible;
-    }
-
-    fn is_visible(&self) -> bool {
-        self.visible
-    }
 }
 
 /// Every UI dynamic object which has constantly changing
@@ -279,12 +1059,9 @@ trait StaticObject {
     /// to update the object's position.
     fn position_area(&mut self, new_area: Rect);
 
-    /// Renders the object to the provided [`Frame`] or in other words UI.
-    fn render<B: Backend>(&self, frame: &mut Frame<B>);
-
-    fn set_visibility(&mut self, visible: bool);
-
-    fn is_visible(&self) -> bool;
+    /// Renders the object to the provided [`Frame`] or in other words UI
+    /// with the colors of the `theme`.
+    fn render<B: Backend>(&self, frame: &mut Frame<B>, theme: &Theme);
 }
 
 struct UILayout;
@@ -304,8 +1081,8 @@ impl UILayout {
             let left_right_areas = Layout::default()
                 .direction(Direction::Horizontal)
                 .constraints([
-                    Constraint::Percentage(30),
-                    Constraint::Percentage(70),
+                    Constraint::Percentage(ui.layout.left_percent),
+                    Constraint::Percentage(100 - ui.layout.left_percent),
                 ])
                 .split(top_area_bottom_object[0]);
 
@@ -316,25 +1093,33 @@ impl UILayout {
                     .constraints([
                         Constraint::Length(CommandsPar::HEIGHT),
                         Constraint::Min(4),
+                        Constraint::Length(ExecutionPar::HEIGHT),
+                        Constraint::Length(InputPar::HEIGHT),
                     ])
                     .split(left_right_areas[0]);
 
                 ui.commands.position_area(left_objects[0]);
                 ui.zone_list.position_area(left_objects[1]);
+                ui.execution.position_area(left_objects[2]);
+                ui.input.position_area(left_objects[3]);
             }
 
-            // Alert list, Active Zones, Volume chart
+            // Alert list, suggested zones, risk, paper trading
             {
                 let right_objects = Layout::default()
                     .direction(Direction::Vertical)
                     .constraints([
-                        Constraint::Length(10),
+                        Constraint::Length(ui.layout.alert_lines),
                         Constraint::Min(5),
-                        Constraint::Length(6),
+                        Constraint::Length(RiskPar::HEIGHT),
+                        Constraint::Length(PaperPar::HEIGHT),
                     ])
                     .split(left_right_areas[1]);
 
                 ui.alerts.position_area(right_objects[0]);
+                ui.suggestions.position_area(right_objects[1]);
+                ui.risk.position_area(right_objects[2]);
+                ui.paper.position_area(right_objects[3]);
             }
         }
     }
@@ -0,0 +1,1009 @@
+use std::{
+    fmt,
+    str::FromStr,
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use futures_util::{future::BoxFuture, StreamExt};
+use reqwest::{
+    blocking::{Client, RequestBuilder},
+    StatusCode,
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::{
+    analysis::Candle,
+    depth::OrderBook,
+    feed::{PriceSource, PriceStream, Ticker, TickerStream},
+    price::Price,
+    rate_limit::RateLimiter,
+    symbol::Symbol,
+};
+
+/// Exchange the market data is read from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VenueKind {
+    #[default]
+    Binance,
+    Bybit,
+    Kraken,
+}
+
+impl VenueKind {
+    /// Creates the public market data client of the venue. Only Binance
+    /// publishes the request weight limit, the `weight_limit` is ignored
+    /// by the others.
+    pub fn connect(self, weight_limit: u64) -> Arc<dyn Venue> {
+        match self {
+            VenueKind::Binance => {
+                Arc::new(BinanceMarket::new(None, weight_limit))
+            }
+            VenueKind::Bybit => Arc::new(BybitMarket::new(None)),
+            VenueKind::Kraken => Arc::new(KrakenMarket::new(None)),
+        }
+    }
+
+    /// Creates the live price stream of the venue, `None` if the venue
+    /// is only polled.
+    pub fn stream(self) -> Option<Arc<dyn PriceStream>> {
+        match self {
+            VenueKind::Binance => Some(Arc::new(BinanceStream::new(None))),
+            VenueKind::Bybit | VenueKind::Kraken => None,
+        }
+    }
+}
+
+impl FromStr for VenueKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "binance" => Ok(VenueKind::Binance),
+            "bybit" => Ok(VenueKind::Bybit),
+            "kraken" => Ok(VenueKind::Kraken),
+            _ => Err(format!("Invalid venue: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for VenueKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            VenueKind::Binance => "binance",
+            VenueKind::Bybit => "bybit",
+            VenueKind::Kraken => "kraken",
+        })
+    }
+}
+
+/// Public market data of an exchange. The venues take and return the
+/// normalized [`Symbol`]s and translate them to their own pair names,
+/// so the zones of `BTC/USDT` are the same on every venue.
+pub trait Venue: PriceSource {
+    fn kind(&self) -> VenueKind;
+
+    /// Reads the last `limit` candles of the `symbol`, each `interval`
+    /// minutes long, the oldest first.
+    fn candles(
+        &self,
+        symbol: &Symbol,
+        interval: u32,
+        limit: u16,
+    ) -> Result<Vec<Candle>, String>;
+
+    /// Reads the best `limit` price levels on each side of the order book
+    /// of the `symbol`.
+    fn depth(&self, symbol: &Symbol, limit: u16) -> Result<OrderBook, String>;
+}
+
+/// Blocking HTTP client of a REST API. The client starts its own thread
+/// so it is created on the first request, the bots which never read
+/// the market don't need it.
+struct Http {
+    endpoint: String,
+    client: OnceLock<Client>,
+}
+
+impl Http {
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn new(endpoint: Option<String>, default: &str) -> Self {
+        Self {
+            endpoint: endpoint.unwrap_or_else(|| default.to_string()),
+            client: OnceLock::new(),
+        }
+    }
+
+    fn get(&self, path: &str) -> RequestBuilder {
+        let client = self.client.get_or_init(|| {
+            Client::builder()
+                .timeout(Self::TIMEOUT)
+                .build()
+                .expect("Creating the HTTP client failed")
+        });
+        client.get(format!("{}{path}", self.endpoint))
+    }
+}
+
+/// Sends the `request` and returns the body of the successful response.
+fn read(request: RequestBuilder) -> Result<String, String> {
+    let response = request.send().map_err(|e| e.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("Reading the market failed ({status})"));
+    }
+    response.text().map_err(|e| e.to_string())
+}
+
+fn parse<'a, T: Deserialize<'a>>(body: &'a str) -> Result<T, String> {
+    serde_json::from_str(body).map_err(|e| e.to_string())
+}
+
+/// Reads a number the exchanges send either as a string or as a number.
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        value => value.as_f64(),
+    }
+}
+
+/// Reads the candle from the `row` of values which has the high, low
+/// and volume at the given indexes.
+fn candle(
+    row: &[Value],
+    high: usize,
+    low: usize,
+    volume: usize,
+) -> Option<Candle> {
+    let get = |i: usize| row.get(i).and_then(number);
+    Some(Candle {
+        high: get(high)?,
+        low: get(low)?,
+        volume: get(volume)?,
+    })
+}
+
+/// Order book of the responses which list the levels as rows starting
+/// with the price and the quantity.
+#[derive(Deserialize)]
+struct Book {
+    #[serde(alias = "b")]
+    bids: Vec<Vec<Value>>,
+    #[serde(alias = "a")]
+    asks: Vec<Vec<Value>>,
+}
+
+impl From<Book> for OrderBook {
+    fn from(book: Book) -> Self {
+        let levels = |rows: Vec<Vec<Value>>| {
+            rows.iter()
+                .filter_map(|row| {
+                    Some((number(row.first()?)?, number(row.get(1)?)?))
+                })
+                .collect()
+        };
+        Self {
+            bids: levels(book.bids),
+            asks: levels(book.asks),
+        }
+    }
+}
+
+/// Reads the ticker from the `price` the exchanges send as a string,
+/// `None` if it is not a valid price.
+fn ticker<S: Into<Symbol>>(symbol: S, price: &str) -> Option<Ticker> {
+    Some(Ticker {
+        symbol: symbol.into(),
+        price: price.parse::<Price>().ok()?,
+    })
+}
+
+/// Keeps only the prices of the `symbols`, which are sorted.
+fn retain(prices: Vec<Ticker>, symbols: &[Symbol]) -> Vec<Ticker> {
+    prices
+        .into_iter()
+        .filter(|price| symbols.binary_search(&price.symbol).is_ok())
+        .collect()
+}
+
+/// Reads the market from the Binance REST API. A single price is read on
+/// its own, more prices are read with one request for all the pairs.
+///
+/// The weight of the requests is kept within the exchange's limit and
+/// a rejected request stops the reading for the time the exchange asked
+/// for. Skipped requests fail without contacting the exchange.
+pub struct BinanceMarket {
+    http: Http,
+    limiter: Mutex<RateLimiter>,
+}
+
+impl BinanceMarket {
+    pub const ENDPOINT: &'static str = "https://api.binance.com";
+    /// Request weights of the price ticker of one and of all the symbols.
+    const SYMBOL_WEIGHT: u64 = 2;
+    const ALL_WEIGHT: u64 = 4;
+
+    /// Reads the market from the `endpoint` or from Binance if it is
+    /// `None`, using at most `weight_limit` of the request weight per
+    /// minute.
+    pub fn new(endpoint: Option<String>, weight_limit: u64) -> Self {
+        Self {
+            http: Http::new(endpoint, Self::ENDPOINT),
+            limiter: Mutex::new(RateLimiter::new(weight_limit)),
+        }
+    }
+
+    /// Sends the `request` of the `weight` if the limit allows it.
+    fn send(
+        &self,
+        request: RequestBuilder,
+        weight: u64,
+    ) -> Result<String, String> {
+        // Requests are sent one at a time so the limiter sees every
+        // response before the next request.
+        let mut limiter = self.limiter.lock().unwrap();
+        let now = Instant::now();
+        if let Err(wait) = limiter.check(weight, now) {
+            let wait = wait.as_secs();
+            return Err(format!("Rate limit reached, waiting {wait}s"));
+        }
+        let response = request.send().map_err(|e| e.to_string())?;
+        let header = |name: &str| -> Option<u64> {
+            response.headers().get(name)?.to_str().ok()?.parse().ok()
+        };
+
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS
+            || status == StatusCode::IM_A_TEAPOT
+        {
+            let retry_after = header("Retry-After").map(Duration::from_secs);
+            limiter.reject(retry_after, now);
+            return Err(format!("Rate limited by the exchange ({status})"));
+        }
+        limiter.accept(weight, header("X-MBX-USED-WEIGHT-1M"), now);
+        drop(limiter);
+        if !status.is_success() {
+            return Err(format!("Reading the market failed ({status})"));
+        }
+        response.text().map_err(|e| e.to_string())
+    }
+}
+
+#[derive(Deserialize)]
+struct BinanceTicker {
+    symbol: String,
+    price: String,
+}
+
+impl BinanceTicker {
+    fn ticker(&self) -> Option<Ticker> {
+        ticker(self.symbol.as_str(), &self.price)
+    }
+}
+
+impl PriceSource for BinanceMarket {
+    fn prices(&self, symbols: &[Symbol]) -> Result<Vec<Ticker>, String> {
+        let request = self.http.get("/api/v3/ticker/price");
+        if let [symbol] = symbols {
+            let request = request.query(&[("symbol", symbol.as_str())]);
+            let body = self.send(request, Self::SYMBOL_WEIGHT)?;
+            let price: BinanceTicker = parse(&body)?;
+            return Ok(price.ticker().into_iter().collect());
+        }
+        let body = self.send(request, Self::ALL_WEIGHT)?;
+        let prices: Vec<BinanceTicker> = parse(&body)?;
+        let prices = prices.iter().filter_map(BinanceTicker::ticker).collect();
+        Ok(retain(prices, symbols))
+    }
+}
+
+impl Venue for BinanceMarket {
+    fn kind(&self) -> VenueKind {
+        VenueKind::Binance
+    }
+
+    fn candles(
+        &self,
+        symbol: &Symbol,
+        interval: u32,
+        limit: u16,
+    ) -> Result<Vec<Candle>, String> {
+        let interval = match interval {
+            m if m % (24 * 60) == 0 => format!("{}d", m / (24 * 60)),
+            m if m % 60 == 0 => format!("{}h", m / 60),
+            m => format!("{m}m"),
+        };
+        let request = self.http.get("/api/v3/klines").query(&[
+            ("symbol", symbol.as_str()),
+            ("interval", &interval),
+            ("limit", &limit.to_string()),
+        ]);
+        let weight = match limit {
+            0..=100 => 1,
+            101..=500 => 2,
+            501..=1000 => 5,
+            _ => 10,
+        };
+        let rows: Vec<Vec<Value>> = parse(&self.send(request, weight)?)?;
+        Ok(rows.iter().filter_map(|row| candle(row, 2, 3, 5)).collect())
+    }
+
+    fn depth(&self, symbol: &Symbol, limit: u16) -> Result<OrderBook, String> {
+        let request = self.http.get("/api/v3/depth").query(&[
+            ("symbol", symbol.as_str()),
+            ("limit", &limit.to_string()),
+        ]);
+        let weight = match limit {
+            0..=100 => 5,
+            101..=500 => 25,
+            501..=1000 => 50,
+            _ => 250,
+        };
+        let book: Book = parse(&self.send(request, weight)?)?;
+        Ok(book.into())
+    }
+}
+
+/// Streams the prices from the Binance WebSocket API. Binance sends the
+/// mini ticker of every symbol whose price changed once per second.
+pub struct BinanceStream {
+    endpoint: String,
+}
+
+/// Message of the combined streams, the data of one of the streams.
+#[derive(Deserialize)]
+struct BinanceStreamMessage {
+    data: BinanceMiniTicker,
+}
+
+#[derive(Deserialize)]
+struct BinanceMiniTicker {
+    #[serde(rename = "s")]
+    symbol: String,
+    /// Price of the last trade.
+    #[serde(rename = "c")]
+    close: String,
+}
+
+impl BinanceStream {
+    pub const ENDPOINT: &'static str = "wss://stream.binance.com:9443";
+
+    /// Streams the prices from the `endpoint` or from Binance
+    /// if it is `None`.
+    pub fn new(endpoint: Option<String>) -> Self {
+        Self {
+            endpoint: endpoint.unwrap_or_else(|| Self::ENDPOINT.to_string()),
+        }
+    }
+}
+
+impl PriceStream for BinanceStream {
+    fn connect(
+        &self,
+        symbols: &[Symbol],
+    ) -> BoxFuture<'static, Result<TickerStream, String>> {
+        let streams: Vec<String> = symbols
+            .iter()
+            .map(|s| format!("{}@miniTicker", s.as_str().to_lowercase()))
+            .collect();
+        let url =
+            format!("{}/stream?streams={}", self.endpoint, streams.join("/"));
+        Box::pin(async move {
+            let (socket, _) = tokio_tungstenite::connect_async(url)
+                .await
+                .map_err(|e| e.to_string())?;
+            let updates = socket.filter_map(|message| async move {
+                match message {
+                    Ok(Message::Text(text)) => {
+                        let message: BinanceStreamMessage =
+                            parse(&text).ok()?;
+                        let mini = message.data;
+                        Some(Ok(ticker(mini.symbol.as_str(), &mini.close)?))
+                    }
+                    // The pings are answered by the socket.
+                    Ok(_) => None,
+                    Err(e) => Some(Err(e.to_string())),
+                }
+            });
+            Ok(updates.boxed())
+        })
+    }
+}
+
+/// Reads the spot market from the Bybit v5 REST API. A single price is read
+/// on its own, more prices are read with one request for all the pairs.
+/// Bybit names the pairs the same way as the normalized symbols.
+pub struct BybitMarket {
+    http: Http,
+}
+
+/// Envelope of all the Bybit responses. The failed requests have
+/// an empty result.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitResponse {
+    ret_code: i64,
+    ret_msg: String,
+    result: Value,
+}
+
+impl BybitResponse {
+    fn result<T: DeserializeOwned>(self) -> Result<T, String> {
+        if self.ret_code != 0 {
+            return Err(format!(
+                "Bybit error {}: {}",
+                self.ret_code, self.ret_msg
+            ));
+        }
+        serde_json::from_value(self.result).map_err(|e| e.to_string())
+    }
+}
+
+#[derive(Deserialize)]
+struct BybitList<T> {
+    list: Vec<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitTicker {
+    symbol: String,
+    last_price: String,
+}
+
+impl BybitMarket {
+    pub const ENDPOINT: &'static str = "https://api.bybit.com";
+
+    /// Reads the market from the `endpoint` or from Bybit if it is `None`.
+    pub fn new(endpoint: Option<String>) -> Self {
+        Self {
+            http: Http::new(endpoint, Self::ENDPOINT),
+        }
+    }
+}
+
+impl PriceSource for BybitMarket {
+    fn prices(&self, symbols: &[Symbol]) -> Result<Vec<Ticker>, String> {
+        let mut request = self
+            .http
+            .get("/v5/market/tickers")
+            .query(&[("category", "spot")]);
+        if let [symbol] = symbols {
+            request = request.query(&[("symbol", symbol.as_str())]);
+        }
+        let response: BybitResponse = parse(&read(request)?)?;
+        let prices = response
+            .result::<BybitList<BybitTicker>>()?
+            .list
+            .into_iter()
+            .filter_map(|t| ticker(t.symbol, &t.last_price))
+            .collect();
+        Ok(retain(prices, symbols))
+    }
+}
+
+impl Venue for BybitMarket {
+    fn kind(&self) -> VenueKind {
+        VenueKind::Bybit
+    }
+
+    fn candles(
+        &self,
+        symbol: &Symbol,
+        interval: u32,
+        limit: u16,
+    ) -> Result<Vec<Candle>, String> {
+        let interval = match interval {
+            m if m == 24 * 60 => String::from("D"),
+            m => m.to_string(),
+        };
+        let request = self.http.get("/v5/market/kline").query(&[
+            ("category", "spot"),
+            ("symbol", symbol.as_str()),
+            ("interval", &interval),
+            ("limit", &limit.to_string()),
+        ]);
+        let response: BybitResponse = parse(&read(request)?)?;
+        // Bybit sends the newest candle first.
+        Ok(response
+            .result::<BybitList<Vec<Value>>>()?
+            .list
+            .iter()
+            .rev()
+            .filter_map(|row| candle(row, 2, 3, 5))
+            .collect())
+    }
+
+    fn depth(&self, symbol: &Symbol, limit: u16) -> Result<OrderBook, String> {
+        let request = self.http.get("/v5/market/orderbook").query(&[
+            ("category", "spot"),
+            ("symbol", symbol.as_str()),
+            ("limit", &limit.to_string()),
+        ]);
+        let response: BybitResponse = parse(&read(request)?)?;
+        Ok(response.result::<Book>()?.into())
+    }
+}
+
+/// Reads the spot market from the Kraken REST API. All the prices are read
+/// with one request for the listed pairs.
+///
+/// Kraken calls Bitcoin `XBT` and Dogecoin `XDG` and answers the older
+/// pairs with their legacy names, e.g. `XXBTZUSD`. Both are translated
+/// to the normalized symbols.
+pub struct KrakenMarket {
+    http: Http,
+}
+
+#[derive(Deserialize)]
+struct KrakenResponse<T> {
+    error: Vec<String>,
+    result: Option<T>,
+}
+
+impl<T> KrakenResponse<T> {
+    fn result(self) -> Result<T, String> {
+        match self.result {
+            Some(result) if self.error.is_empty() => Ok(result),
+            _ => Err(format!("Kraken error: {}", self.error.join(", "))),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct KrakenTicker {
+    /// Price and volume of the last trade.
+    c: (String, String),
+}
+
+impl KrakenMarket {
+    pub const ENDPOINT: &'static str = "https://api.kraken.com";
+
+    /// Reads the market from the `endpoint` or from Kraken if it is `None`.
+    pub fn new(endpoint: Option<String>) -> Self {
+        Self {
+            http: Http::new(endpoint, Self::ENDPOINT),
+        }
+    }
+
+    /// Kraken names of the assets paired with their common names.
+    const ALIASES: [(&'static str, &'static str); 2] =
+        [("XBT", "BTC"), ("XDG", "DOGE")];
+
+    /// Kraken name of the `symbol`, `BTCUSDT` is `XBTUSDT`.
+    fn pair(symbol: &Symbol) -> String {
+        let mut pair = symbol.as_str().to_string();
+        for (alias, asset) in Self::ALIASES {
+            pair = replace_asset(&pair, asset, alias);
+        }
+        pair
+    }
+
+    /// Normalized symbol of the Kraken `pair`, `XXBTZUSD` is `BTCUSD`.
+    fn symbol(pair: &str) -> Symbol {
+        let legacy = pair.len() == 8
+            && pair.starts_with('X')
+            && matches!(pair.as_bytes()[4], b'X' | b'Z');
+        let mut name = if legacy {
+            format!("{}{}", &pair[1..4], &pair[5..])
+        } else {
+            pair.to_string()
+        };
+        for (alias, asset) in Self::ALIASES {
+            name = replace_asset(&name, alias, asset);
+        }
+        Symbol::new(&name)
+    }
+}
+
+/// Replaces the base or the quote `asset` of the `pair` with the `other`.
+fn replace_asset(pair: &str, asset: &str, other: &str) -> String {
+    if let Some(quote) = pair.strip_prefix(asset) {
+        format!("{other}{quote}")
+    } else if let Some(base) = pair.strip_suffix(asset) {
+        format!("{base}{other}")
+    } else {
+        pair.to_string()
+    }
+}
+
+impl PriceSource for KrakenMarket {
+    fn prices(&self, symbols: &[Symbol]) -> Result<Vec<Ticker>, String> {
+        let pairs: Vec<String> = symbols.iter().map(Self::pair).collect();
+        let request = self
+            .http
+            .get("/0/public/Ticker")
+            .query(&[("pair", pairs.join(","))]);
+        let response: KrakenResponse<serde_json::Map<String, Value>> =
+            parse(&read(request)?)?;
+        let prices = response
+            .result()?
+            .into_iter()
+            .filter_map(|(pair, value)| {
+                let last: KrakenTicker = serde_json::from_value(value).ok()?;
+                ticker(Self::symbol(&pair), &last.c.0)
+            })
+            .collect();
+        Ok(retain(prices, symbols))
+    }
+}
+
+impl Venue for KrakenMarket {
+    fn kind(&self) -> VenueKind {
+        VenueKind::Kraken
+    }
+
+    /// Kraken always sends its last 720 candles, only the last `limit`
+    /// of them are kept.
+    fn candles(
+        &self,
+        symbol: &Symbol,
+        interval: u32,
+        limit: u16,
+    ) -> Result<Vec<Candle>, String> {
+        let request = self.http.get("/0/public/OHLC").query(&[
+            ("pair", Self::pair(symbol)),
+            ("interval", interval.to_string()),
+        ]);
+        let response: KrakenResponse<serde_json::Map<String, Value>> =
+            parse(&read(request)?)?;
+        // The result has the candles under the pair name next to
+        // the `last` timestamp.
+        let rows: Vec<Vec<Value>> = response
+            .result()?
+            .into_iter()
+            .find_map(|(_, value)| serde_json::from_value(value).ok())
+            .ok_or_else(|| format!("No candles of {symbol} on Kraken"))?;
+        let skip = rows.len().saturating_sub(usize::from(limit));
+        Ok(rows[skip..]
+            .iter()
+            .filter_map(|row| candle(row, 2, 3, 6))
+            .collect())
+    }
+
+    fn depth(&self, symbol: &Symbol, limit: u16) -> Result<OrderBook, String> {
+        let request = self.http.get("/0/public/Depth").query(&[
+            ("pair", Self::pair(symbol)),
+            ("count", limit.to_string()),
+        ]);
+        let response: KrakenResponse<serde_json::Map<String, Value>> =
+            parse(&read(request)?)?;
+        // The result has the book under the pair name.
+        let book: Book = response
+            .result()?
+            .into_iter()
+            .find_map(|(_, value)| serde_json::from_value(value).ok())
+            .ok_or_else(|| format!("No order book of {symbol} on Kraken"))?;
+        Ok(book.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread,
+        time::Duration,
+    };
+
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    use super::{
+        BinanceMarket, BinanceStream, BybitMarket, KrakenMarket, Venue,
+    };
+    use crate::{
+        analysis::Candle,
+        feed::{PriceSource, PriceStream},
+        price::Price,
+        symbol::Symbol,
+    };
+
+    /// Answers one connection per response with the status, headers and
+    /// body of the response. Returns the received request lines.
+    fn stub(
+        responses: Vec<(&'static str, &'static str, &'static str)>,
+    ) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, headers, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                reader.read_line(&mut request).unwrap();
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\n{headers}\
+                     Content-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+                requests.push(request.trim_end().to_string());
+            }
+            requests
+        });
+        (url, server)
+    }
+
+    fn ok(body: &'static str) -> (&'static str, &'static str, &'static str) {
+        ("200 OK", "", body)
+    }
+
+    /// The venues get the symbols sorted.
+    fn symbols(names: &[&str]) -> Vec<Symbol> {
+        let mut symbols: Vec<Symbol> =
+            names.iter().map(|name| Symbol::new(name)).collect();
+        symbols.sort();
+        symbols
+    }
+
+    /// Writes the prices exactly as they were parsed.
+    fn prices(venue: &dyn Venue, names: &[&str]) -> Vec<(String, String)> {
+        venue
+            .prices(&symbols(names))
+            .unwrap()
+            .into_iter()
+            .map(|p| (p.symbol.to_string(), p.price.to_string()))
+            .collect()
+    }
+
+    fn pairs<const N: usize>(
+        prices: [(&str, &str); N],
+    ) -> Vec<(String, String)> {
+        prices.map(|(s, p)| (s.to_owned(), p.to_owned())).into()
+    }
+
+    fn candles(venue: &dyn Venue, name: &str) -> Vec<Candle> {
+        venue.candles(&Symbol::new(name), 60, 500).unwrap()
+    }
+
+    /// Both fixtures hold the same two hourly candles, the oldest first.
+    fn assert_candles(candles: &[Candle], volumes: [f64; 2]) {
+        let highs: Vec<(f64, f64)> =
+            candles.iter().map(|c| (c.high, c.low)).collect();
+        assert_eq!(highs, [(67350.0, 67020.4), (67420.5, 67150.1)]);
+        let volume: Vec<f64> = candles.iter().map(|c| c.volume).collect();
+        assert_eq!(volume, volumes);
+    }
+
+    #[test]
+    fn binance_reads_recorded_market() {
+        let (url, server) = stub(vec![
+            ok(include_str!("../fixtures/binance_ticker.json")),
+            ok(include_str!("../fixtures/binance_klines.json")),
+        ]);
+        let binance = BinanceMarket::new(Some(url), 6000);
+
+        assert_eq!(
+            prices(&binance, &["BTCUSDT", "ETHUSDT"]),
+            pairs([("BTCUSDT", "67234.01"), ("ETHUSDT", "3638.52")])
+        );
+        assert_candles(&candles(&binance, "BTC/USDT"), [955.214, 812.431]);
+        assert_eq!(
+            server.join().unwrap(),
+            [
+                "GET /api/v3/ticker/price HTTP/1.1",
+                "GET /api/v3/klines?symbol=BTCUSDT&interval=1h&limit=500 \
+                 HTTP/1.1"
+            ]
+        );
+    }
+
+    #[test]
+    fn bybit_reads_recorded_market() {
+        let (url, server) = stub(vec![
+            ok(include_str!("../fixtures/bybit_tickers.json")),
+            ok(include_str!("../fixtures/bybit_kline.json")),
+            ok(r#"{"retCode": 10001, "retMsg": "Not supported symbols",
+                   "result": {}}"#),
+        ]);
+        let bybit = BybitMarket::new(Some(url));
+
+        assert_eq!(
+            prices(&bybit, &["SOLUSDT", "BTCUSDT"]),
+            pairs([("SOLUSDT", "151.13"), ("BTCUSDT", "67234.1")])
+        );
+        // Bybit sends the newest candle first.
+        assert_candles(&candles(&bybit, "bybit:BTC-USDT"), [701.9, 612.41]);
+        assert_eq!(
+            bybit.prices(&symbols(&["XYZUSDT"])).unwrap_err(),
+            "Bybit error 10001: Not supported symbols"
+        );
+        let requests = server.join().unwrap();
+        assert_eq!(
+            requests[1],
+            "GET /v5/market/kline?category=spot&symbol=BTCUSDT&interval=60\
+             &limit=500 HTTP/1.1"
+        );
+    }
+
+    #[test]
+    fn kraken_pairs_are_translated() {
+        let (url, server) = stub(vec![
+            ok(include_str!("../fixtures/kraken_ticker.json")),
+            ok(include_str!("../fixtures/kraken_ohlc.json")),
+            ok(include_str!("../fixtures/kraken_error.json")),
+        ]);
+        let kraken = KrakenMarket::new(Some(url));
+
+        // The legacy XETHZUSD is the ETHUSD pair.
+        assert_eq!(
+            prices(&kraken, &["BTCUSDT", "ETHUSD"]),
+            pairs([("BTCUSDT", "67240.1"), ("ETHUSD", "3640.12")])
+        );
+        assert_candles(&candles(&kraken, "kraken:BTC/USD"), [88.21, 120.55]);
+        assert_eq!(
+            kraken.prices(&symbols(&["XYZUSD"])).unwrap_err(),
+            "Kraken error: EQuery:Unknown asset pair"
+        );
+        assert_eq!(
+            server.join().unwrap()[..2],
+            [
+                "GET /0/public/Ticker?pair=XBTUSDT%2CETHUSD HTTP/1.1",
+                "GET /0/public/OHLC?pair=XBTUSD&interval=60 HTTP/1.1"
+            ]
+        );
+    }
+
+    /// Lets the limiter of the `binance` market act as if `secs` passed.
+    fn elapse(binance: &BinanceMarket, secs: u64) {
+        let mut limiter = binance.limiter.lock().unwrap();
+        limiter.rewind(Duration::from_secs(secs));
+    }
+
+    #[test]
+    fn binance_keeps_within_the_weight_limit() {
+        let (url, server) = stub(vec![
+            (
+                "200 OK",
+                "X-MBX-USED-WEIGHT-1M: 8\r\n",
+                include_str!("../fixtures/binance_ticker.json"),
+            ),
+            ok(r#"{"symbol": "ETHUSDT", "price": "1501.0"}"#),
+        ]);
+        let binance = BinanceMarket::new(Some(url), 9);
+
+        assert_eq!(prices(&binance, &["BTCUSDT", "ETHUSDT"]).len(), 2);
+        // 8 of 9 used, the next request waits for the next minute.
+        let err = binance.prices(&symbols(&["ETHUSDT"])).unwrap_err();
+        assert!(err.starts_with("Rate limit reached"), "{err}");
+
+        elapse(&binance, 60);
+        assert_eq!(
+            prices(&binance, &["ETHUSDT"]),
+            pairs([("ETHUSDT", "1501")])
+        );
+        assert_eq!(
+            server.join().unwrap(),
+            [
+                "GET /api/v3/ticker/price HTTP/1.1",
+                "GET /api/v3/ticker/price?symbol=ETHUSDT HTTP/1.1"
+            ]
+        );
+    }
+
+    #[test]
+    fn binance_backs_off_when_rejected() {
+        let (url, server) = stub(vec![
+            ("429 Too Many Requests", "Retry-After: 120\r\n", ""),
+            ("418 I'm a teapot", "", ""),
+            ok(r#"{"symbol": "ETHUSDT", "price": "1500.0"}"#),
+        ]);
+        let binance = BinanceMarket::new(Some(url), 6000);
+        let eth = symbols(&["ETHUSDT"]);
+
+        let err = binance.prices(&eth).unwrap_err();
+        assert!(err.contains("429"), "{err}");
+        // The exchange is not contacted until the retry time passes.
+        elapse(&binance, 60);
+        let err = binance.prices(&eth).unwrap_err();
+        assert!(err.starts_with("Rate limit reached"), "{err}");
+
+        elapse(&binance, 61);
+        let err = binance.prices(&eth).unwrap_err();
+        assert!(err.contains("418"), "{err}");
+        // Without a retry time the wait doubles with every rejection
+        // in a row.
+        elapse(&binance, 31);
+        let err = binance.prices(&eth).unwrap_err();
+        assert!(err.starts_with("Rate limit reached"), "{err}");
+        elapse(&binance, 30);
+        assert_eq!(binance.prices(&eth).unwrap()[0].price, Price::new(1500, 0));
+        assert_eq!(server.join().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn every_venue_reads_the_order_book() {
+        let (url, server) = stub(vec![
+            ok(include_str!("../fixtures/binance_depth.json")),
+            ok(include_str!("../fixtures/bybit_orderbook.json")),
+            ok(include_str!("../fixtures/kraken_depth.json")),
+        ]);
+        let venues: [Box<dyn Venue>; 3] = [
+            Box::new(BinanceMarket::new(Some(url.clone()), 6000)),
+            Box::new(BybitMarket::new(Some(url.clone()))),
+            Box::new(KrakenMarket::new(Some(url))),
+        ];
+
+        // All the fixtures hold the same book.
+        for venue in venues {
+            let book = venue.depth(&Symbol::new("ETHUSD"), 100).unwrap();
+            assert_eq!(book.bids.len(), 4, "{}", venue.kind());
+            assert_eq!(book.bids[2], (1045.0, 40.0), "{}", venue.kind());
+            assert_eq!(book.asks[0], (1051.0, 1.5), "{}", venue.kind());
+        }
+        assert_eq!(
+            server.join().unwrap(),
+            [
+                "GET /api/v3/depth?symbol=ETHUSD&limit=100 HTTP/1.1",
+                "GET /v5/market/orderbook?category=spot&symbol=ETHUSD\
+                 &limit=100 HTTP/1.1",
+                "GET /0/public/Depth?pair=ETHUSD&count=100 HTTP/1.1"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn binance_streams_the_mini_tickers() {
+        let listener =
+            tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            // The handshake request is only peeked at, the socket reads it.
+            let mut request = [0; 1024];
+            let read = socket.peek(&mut request).await.unwrap();
+            let request = String::from_utf8_lossy(&request[..read]);
+            let line = request.lines().next().unwrap().to_owned();
+            let mut socket =
+                tokio_tungstenite::accept_async(socket).await.unwrap();
+            for (symbol, price) in [
+                ("ETHUSDT", "3638.52"),
+                ("ETHUSDT", "-"),
+                ("BTCUSDT", "67234"),
+            ] {
+                let data = format!(r#"{{"s": "{symbol}", "c": "{price}"}}"#);
+                let message =
+                    format!(r#"{{"stream": "x@miniTicker", "data": {data}}}"#);
+                socket.send(Message::Text(message)).await.unwrap();
+            }
+            socket.close(None).await.unwrap();
+            line
+        });
+
+        let stream = BinanceStream::new(Some(url));
+        let updates = stream
+            .connect(&symbols(&["ETHUSDT", "BTCUSDT"]))
+            .await
+            .unwrap();
+        // The invalid price is skipped.
+        let prices: Vec<(String, String)> = updates
+            .map(|p| {
+                let p = p.unwrap();
+                (p.symbol.to_string(), p.price.to_string())
+            })
+            .collect()
+            .await;
+        assert_eq!(
+            prices,
+            pairs([("ETHUSDT", "3638.52"), ("BTCUSDT", "67234")])
+        );
+        assert_eq!(
+            server.await.unwrap(),
+            "GET /stream?streams=btcusdt@miniTicker/ethusdt@miniTicker \
+             HTTP/1.1"
+        );
+    }
+}